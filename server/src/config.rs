@@ -1,6 +1,27 @@
 use serde::{Deserialize, Serialize};
 use std::env;
 
+/// Where `MLService` actually runs inference: in-process ONNX Runtime, or
+/// dispatched to a remote worker pool over the cluster message bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InferenceBackendKind {
+    Local,
+    Remote,
+}
+
+impl std::str::FromStr for InferenceBackendKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "local" => Ok(Self::Local),
+            "remote" => Ok(Self::Remote),
+            other => Err(anyhow::anyhow!("unknown inference backend: {}", other)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub database_url: String,
@@ -10,6 +31,28 @@ pub struct Config {
     pub model_version: String,
     pub enable_telemetry: bool,
     pub log_level: String,
+    pub weather_api_url: String,
+    /// Softmax temperature applied to classifier logits before thresholding.
+    /// Values below 1.0 sharpen the distribution, above 1.0 soften it; use
+    /// this to calibrate an under/over-confident model without retraining.
+    pub ml_temperature: f64,
+    /// Whether `MLService` runs ONNX locally or dispatches to the cluster.
+    pub inference_backend: InferenceBackendKind,
+    /// Message bus / broker URL used for worker discovery and job dispatch
+    /// when `inference_backend` is `Remote`.
+    pub inference_broker_url: String,
+    /// Per-job timeout before falling back to a local model (if loaded) or
+    /// failing the request.
+    pub inference_worker_timeout_ms: u64,
+    /// Caps how many `Scheduler` tasks (embedding, batch diagnosis, ...)
+    /// run concurrently, independent of how many are enqueued.
+    pub scheduler_max_concurrency: usize,
+    /// Base URL agronomists' playbook documents are ingested from; see
+    /// `services::playbook_ingestion`.
+    pub playbook_source_url: String,
+    /// Directory versioned export/dump archives are written to; see
+    /// `services::scheduler::run_export_dump`.
+    pub dump_dir: String,
 }
 
 impl Config {
@@ -33,6 +76,29 @@ impl Config {
                 .unwrap_or(false),
             log_level: env::var("LOG_LEVEL")
                 .unwrap_or_else(|_| "info".to_string()),
+            weather_api_url: env::var("WEATHER_API_URL")
+                .unwrap_or_else(|_| "https://api.open-meteo.com/v1".to_string()),
+            ml_temperature: env::var("ML_TEMPERATURE")
+                .unwrap_or_else(|_| "1.0".to_string())
+                .parse()
+                .unwrap_or(1.0),
+            inference_backend: env::var("INFERENCE_BACKEND")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(InferenceBackendKind::Local),
+            inference_broker_url: env::var("INFERENCE_BROKER_URL")
+                .unwrap_or_else(|_| "http://localhost:4222".to_string()),
+            inference_worker_timeout_ms: env::var("INFERENCE_WORKER_TIMEOUT_MS")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse()
+                .unwrap_or(5000),
+            scheduler_max_concurrency: env::var("SCHEDULER_MAX_CONCURRENCY")
+                .unwrap_or_else(|_| "4".to_string())
+                .parse()
+                .unwrap_or(4),
+            playbook_source_url: env::var("PLAYBOOK_SOURCE_URL")
+                .unwrap_or_else(|_| "https://playbooks.leaflens.internal/v1".to_string()),
+            dump_dir: env::var("DUMP_DIR").unwrap_or_else(|_| "./dumps".to_string()),
         };
 
         Ok(config)
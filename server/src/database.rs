@@ -1,6 +1,8 @@
+use serde::Serialize;
 use sqlx::{PgPool, Row};
 use std::collections::HashMap;
 
+#[derive(Clone)]
 pub struct Database {
     pub pool: PgPool,
 }
@@ -25,13 +27,14 @@ impl Database {
         confidence: f64,
         crop_type: Option<&str>,
         metadata: Option<&serde_json::Value>,
+        feature_vector: Option<&[f32]>,
     ) -> anyhow::Result<uuid::Uuid> {
         let id = uuid::Uuid::new_v4();
-        
+
         sqlx::query!(
             r#"
-            INSERT INTO diagnoses (id, user_id, image_data, predictions, confidence, crop_type, metadata, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, NOW())
+            INSERT INTO diagnoses (id, user_id, image_data, predictions, confidence, crop_type, metadata, feature_vector, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW())
             "#,
             id,
             user_id,
@@ -39,7 +42,8 @@ impl Database {
             predictions,
             confidence,
             crop_type,
-            metadata
+            metadata,
+            feature_vector.map(|v| v.to_vec()).as_deref()
         )
         .execute(&self.pool)
         .await?;
@@ -47,6 +51,44 @@ impl Database {
         Ok(id)
     }
 
+    /// Bulk-loads stored embeddings to (re)build the in-process case-memory
+    /// ANN index. `label` is the top predicted label, pulled out of the
+    /// stored `predictions` JSON for display alongside a match.
+    pub async fn get_diagnosis_embeddings(
+        &self,
+        limit: i64,
+    ) -> anyhow::Result<Vec<(uuid::Uuid, Vec<f32>, String)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT id, predictions, feature_vector
+            FROM diagnoses
+            WHERE feature_vector IS NOT NULL
+            ORDER BY created_at DESC
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let embeddings = rows
+            .into_iter()
+            .filter_map(|row| {
+                let vector = row.feature_vector?;
+                let label = row
+                    .predictions
+                    .get(0)
+                    .and_then(|p| p.get("label"))
+                    .and_then(|l| l.as_str())
+                    .unwrap_or("Unknown")
+                    .to_string();
+                Some((row.id, vector, label))
+            })
+            .collect();
+
+        Ok(embeddings)
+    }
+
     pub async fn get_diagnosis(&self, id: uuid::Uuid) -> anyhow::Result<Option<DiagnosisRecord>> {
         let row = sqlx::query!(
             r#"
@@ -189,11 +231,314 @@ impl Database {
         Ok(outbreaks)
     }
 
+    pub async fn get_outbreak_candidates(
+        &self,
+        min_lat: f64,
+        max_lat: f64,
+        min_lon: f64,
+        max_lon: f64,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> anyhow::Result<Vec<OutbreakReport>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT id, user_id, crop_type, disease, latitude, longitude, confidence, metadata, created_at
+            FROM outbreak_reports
+            WHERE latitude BETWEEN $1 AND $2 AND longitude BETWEEN $3 AND $4
+              AND created_at >= $5
+            ORDER BY created_at DESC
+            "#,
+            min_lat,
+            max_lat,
+            min_lon,
+            max_lon,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let candidates = rows
+            .into_iter()
+            .map(|row| OutbreakReport {
+                id: row.id,
+                user_id: row.user_id,
+                crop_type: row.crop_type,
+                disease: row.disease,
+                latitude: row.latitude,
+                longitude: row.longitude,
+                confidence: row.confidence,
+                metadata: row.metadata,
+                created_at: row.created_at,
+            })
+            .collect();
+
+        Ok(candidates)
+    }
+
+    /// All outbreak reports, most recent first, capped at `limit`. Used to
+    /// (re)build the full-text search index, which isn't scoped to a
+    /// region the way the map-facing queries above are.
+    pub async fn get_recent_outbreaks(&self, limit: i64) -> anyhow::Result<Vec<OutbreakReport>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT id, user_id, crop_type, disease, latitude, longitude, confidence, metadata, created_at
+            FROM outbreak_reports
+            ORDER BY created_at DESC
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let outbreaks = rows
+            .into_iter()
+            .map(|row| OutbreakReport {
+                id: row.id,
+                user_id: row.user_id,
+                crop_type: row.crop_type,
+                disease: row.disease,
+                latitude: row.latitude,
+                longitude: row.longitude,
+                confidence: row.confidence,
+                metadata: row.metadata,
+                created_at: row.created_at,
+            })
+            .collect();
+
+        Ok(outbreaks)
+    }
+
+    /// Total row count across every outbreak report ever saved, independent
+    /// of `get_recent_outbreaks`'s window — used for fleet-stats totals that
+    /// shouldn't plateau at whatever limit the breakdown queries use.
+    pub async fn count_outbreaks(&self) -> anyhow::Result<i64> {
+        let row = sqlx::query!("SELECT COUNT(*) as count FROM outbreak_reports")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(row.count.unwrap_or(0))
+    }
+
+    /// Nearest-neighbor query around a point. Prefilters with a bounding
+    /// box sized from `radius_km` (using the spatial index added in
+    /// `20260115000000_outbreak_spatial_index.sql`), then re-ranks the
+    /// candidates in Rust with the exact haversine distance, since this
+    /// deployment doesn't have PostGIS for a true `<->`/`ST_DWithin` query.
+    ///
+    /// `radius_km`/`limit` are expected to already be bounded by the caller
+    /// (see `utils::validate_near_query`); `NEAR_QUERY_CANDIDATE_CAP` is a
+    /// backstop on top of that so a dense bounding box still can't pull an
+    /// unbounded number of rows into memory before the exact-distance
+    /// filter and `limit` truncation run.
+    pub async fn get_outbreaks_near(
+        &self,
+        lat: f64,
+        lon: f64,
+        radius_km: f64,
+        limit: i64,
+    ) -> anyhow::Result<Vec<OutbreakReport>> {
+        const NEAR_QUERY_CANDIDATE_CAP: i64 = 2000;
+
+        let lat_delta = radius_km / 111.0;
+        let lon_delta = radius_km / (111.0 * lat.to_radians().cos().abs().max(0.01));
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT id, user_id, crop_type, disease, latitude, longitude, confidence, metadata, created_at
+            FROM outbreak_reports
+            WHERE latitude BETWEEN $1 AND $2 AND longitude BETWEEN $3 AND $4
+            ORDER BY created_at DESC
+            LIMIT $5
+            "#,
+            lat - lat_delta,
+            lat + lat_delta,
+            lon - lon_delta,
+            lon + lon_delta,
+            NEAR_QUERY_CANDIDATE_CAP,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut candidates: Vec<OutbreakReport> = rows
+            .into_iter()
+            .map(|row| OutbreakReport {
+                id: row.id,
+                user_id: row.user_id,
+                crop_type: row.crop_type,
+                disease: row.disease,
+                latitude: row.latitude,
+                longitude: row.longitude,
+                confidence: row.confidence,
+                metadata: row.metadata,
+                created_at: row.created_at,
+            })
+            .filter(|report| {
+                crate::utils::calculate_distance(lat, lon, report.latitude, report.longitude)
+                    <= radius_km
+            })
+            .collect();
+
+        candidates.sort_by(|a, b| {
+            let da = crate::utils::calculate_distance(lat, lon, a.latitude, a.longitude);
+            let db = crate::utils::calculate_distance(lat, lon, b.latitude, b.longitude);
+            da.partial_cmp(&db).unwrap()
+        });
+        candidates.truncate(limit as usize);
+
+        Ok(candidates)
+    }
+
+    // Crop knowledge base
+    pub async fn get_crops(&self) -> anyhow::Result<Vec<CropRecord>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT canonical_name, synonyms, botanical_name, family, habitat, diseases
+            FROM crops
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let crops = rows
+            .into_iter()
+            .map(|row| CropRecord {
+                canonical_name: row.canonical_name,
+                synonyms: row.synonyms.unwrap_or_default(),
+                botanical_name: row.botanical_name,
+                family: row.family,
+                habitat: row.habitat,
+                diseases: row.diseases,
+            })
+            .collect();
+
+        Ok(crops)
+    }
+
+    // Task queue
+    pub async fn enqueue_task(&self, kind: &crate::models::TaskKind) -> anyhow::Result<uuid::Uuid> {
+        let id = uuid::Uuid::new_v4();
+        let content = serde_json::to_value(kind)?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO tasks (id, kind, content, status)
+            VALUES ($1, $2, $3, 'enqueued')
+            "#,
+            id,
+            kind.name(),
+            content
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Claims the oldest enqueued task with `FOR UPDATE SKIP LOCKED` so
+    /// concurrent scheduler workers never grab the same row, and flips it
+    /// to `processing` atomically in the same transaction.
+    pub async fn next_task(&self) -> anyhow::Result<Option<Task>> {
+        let mut tx = self.pool.begin().await?;
+
+        let row = sqlx::query!(
+            r#"
+            SELECT id, kind, content, status, retry_count, error, result, created_at, updated_at
+            FROM tasks
+            WHERE status = 'enqueued'
+            ORDER BY created_at
+            FOR UPDATE SKIP LOCKED
+            LIMIT 1
+            "#
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(row) = row else {
+            tx.commit().await?;
+            return Ok(None);
+        };
+
+        sqlx::query!(
+            "UPDATE tasks SET status = 'processing', updated_at = NOW() WHERE id = $1",
+            row.id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(Task {
+            id: row.id,
+            kind: row.kind,
+            content: row.content,
+            status: "processing".to_string(),
+            retry_count: row.retry_count,
+            error: row.error,
+            result: row.result,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }))
+    }
+
+    /// Records a task's final (or intermediate, for a crash-resumable
+    /// transition) status, optionally attaching its result payload.
+    pub async fn set_task_result(
+        &self,
+        id: uuid::Uuid,
+        status: crate::models::TaskStatus,
+        result: Option<&serde_json::Value>,
+        error: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let retry_increment = matches!(status, crate::models::TaskStatus::Failed) as i32;
+
+        sqlx::query!(
+            r#"
+            UPDATE tasks
+            SET status = $2, result = COALESCE($3, result), error = $4, retry_count = retry_count + $5, updated_at = NOW()
+            WHERE id = $1
+            "#,
+            id,
+            status.as_str(),
+            result,
+            error,
+            retry_increment
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_task(&self, id: uuid::Uuid) -> anyhow::Result<Option<Task>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT id, kind, content, status, retry_count, error, result, created_at, updated_at
+            FROM tasks
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| Task {
+            id: row.id,
+            kind: row.kind,
+            content: row.content,
+            status: row.status,
+            retry_count: row.retry_count,
+            error: row.error,
+            result: row.result,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }))
+    }
+
     // Plugin related queries
     pub async fn get_plugins(&self) -> anyhow::Result<Vec<Plugin>> {
         let rows = sqlx::query!(
             r#"
-            SELECT id, name, version, description, crop_types, is_active, created_at, updated_at
+            SELECT id, name, version, description, crop_types, is_active, wasm_path, created_at, updated_at
             FROM plugins
             WHERE is_active = true
             ORDER BY name
@@ -211,6 +556,39 @@ impl Database {
                 description: row.description,
                 crop_types: row.crop_types,
                 is_active: row.is_active,
+                wasm_path: row.wasm_path,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+            })
+            .collect();
+
+        Ok(plugins)
+    }
+
+    /// The full plugin registry, active or not; used by the export/dump
+    /// job and anywhere else that needs the complete set rather than just
+    /// what's currently served.
+    pub async fn get_all_plugins(&self) -> anyhow::Result<Vec<Plugin>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT id, name, version, description, crop_types, is_active, wasm_path, created_at, updated_at
+            FROM plugins
+            ORDER BY name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let plugins = rows
+            .into_iter()
+            .map(|row| Plugin {
+                id: row.id,
+                name: row.name,
+                version: row.version,
+                description: row.description,
+                crop_types: row.crop_types,
+                is_active: row.is_active,
+                wasm_path: row.wasm_path,
                 created_at: row.created_at,
                 updated_at: row.updated_at,
             })
@@ -222,7 +600,7 @@ impl Database {
     pub async fn get_plugin(&self, id: uuid::Uuid) -> anyhow::Result<Option<Plugin>> {
         let row = sqlx::query!(
             r#"
-            SELECT id, name, version, description, crop_types, is_active, created_at, updated_at
+            SELECT id, name, version, description, crop_types, is_active, wasm_path, created_at, updated_at
             FROM plugins
             WHERE id = $1
             "#,
@@ -239,6 +617,7 @@ impl Database {
                 description: row.description,
                 crop_types: row.crop_types,
                 is_active: row.is_active,
+                wasm_path: row.wasm_path,
                 created_at: row.created_at,
                 updated_at: row.updated_at,
             }))
@@ -246,6 +625,116 @@ impl Database {
             Ok(None)
         }
     }
+
+    // Playbook related queries
+    pub async fn get_all_playbooks(&self) -> anyhow::Result<Vec<PlaybookRecord>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT code, title, description, steps, safety_notes, organic_alternatives,
+                   prevention_tips, content_version, last_updated
+            FROM playbooks
+            ORDER BY code
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let playbooks = rows
+            .into_iter()
+            .map(|row| PlaybookRecord {
+                code: row.code,
+                title: row.title,
+                description: row.description,
+                steps: row.steps,
+                safety_notes: row.safety_notes,
+                organic_alternatives: row.organic_alternatives,
+                prevention_tips: row.prevention_tips,
+                content_version: row.content_version,
+                last_updated: row.last_updated,
+            })
+            .collect();
+
+        Ok(playbooks)
+    }
+
+    pub async fn get_playbook_by_code(&self, code: &str) -> anyhow::Result<Option<PlaybookRecord>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT code, title, description, steps, safety_notes, organic_alternatives,
+                   prevention_tips, content_version, last_updated
+            FROM playbooks
+            WHERE code = $1
+            "#,
+            code
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| PlaybookRecord {
+            code: row.code,
+            title: row.title,
+            description: row.description,
+            steps: row.steps,
+            safety_notes: row.safety_notes,
+            organic_alternatives: row.organic_alternatives,
+            prevention_tips: row.prevention_tips,
+            content_version: row.content_version,
+            last_updated: row.last_updated,
+        }))
+    }
+
+    /// Inserts or refreshes a playbook, keyed by `code`. Skips the write
+    /// entirely when `content_version` matches what's already stored, so
+    /// re-running ingestion over unchanged documents is a no-op. Returns
+    /// `Some(true)` for a new row, `Some(false)` for an updated one, and
+    /// `None` when nothing changed.
+    pub async fn upsert_playbook(
+        &self,
+        code: &str,
+        title: &str,
+        description: &str,
+        steps: &serde_json::Value,
+        safety_notes: &[String],
+        organic_alternatives: Option<&[String]>,
+        prevention_tips: &[String],
+        content_version: &str,
+        last_updated: chrono::DateTime<chrono::Utc>,
+    ) -> anyhow::Result<Option<bool>> {
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO playbooks (
+                code, title, description, steps, safety_notes, organic_alternatives,
+                prevention_tips, content_version, last_updated
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (code) DO UPDATE SET
+                title = EXCLUDED.title,
+                description = EXCLUDED.description,
+                steps = EXCLUDED.steps,
+                safety_notes = EXCLUDED.safety_notes,
+                organic_alternatives = EXCLUDED.organic_alternatives,
+                prevention_tips = EXCLUDED.prevention_tips,
+                content_version = EXCLUDED.content_version,
+                last_updated = EXCLUDED.last_updated,
+                updated_at = NOW()
+            WHERE playbooks.content_version IS DISTINCT FROM EXCLUDED.content_version
+            RETURNING (xmax = 0) AS "inserted!"
+            "#,
+            code,
+            title,
+            description,
+            steps,
+            safety_notes,
+            organic_alternatives,
+            prevention_tips,
+            content_version,
+            last_updated,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| row.inserted))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -260,7 +749,7 @@ pub struct DiagnosisRecord {
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct OutbreakReport {
     pub id: uuid::Uuid,
     pub user_id: Option<uuid::Uuid>,
@@ -274,6 +763,45 @@ pub struct OutbreakReport {
 }
 
 #[derive(Debug, Clone)]
+pub struct CropRecord {
+    pub canonical_name: String,
+    pub synonyms: Vec<String>,
+    pub botanical_name: String,
+    pub family: String,
+    pub habitat: String,
+    pub diseases: serde_json::Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct Task {
+    pub id: uuid::Uuid,
+    pub kind: String,
+    pub content: serde_json::Value,
+    pub status: String,
+    pub retry_count: i32,
+    pub error: Option<String>,
+    /// Set once the task finishes; e.g. the per-image predictions produced
+    /// by a `BatchDiagnose` job, so `GET /v1/diagnose/tasks/:id` has
+    /// something to return besides a status.
+    pub result: Option<serde_json::Value>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlaybookRecord {
+    pub code: String,
+    pub title: String,
+    pub description: String,
+    pub steps: serde_json::Value,
+    pub safety_notes: Vec<String>,
+    pub organic_alternatives: Option<Vec<String>>,
+    pub prevention_tips: Vec<String>,
+    pub content_version: String,
+    pub last_updated: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct Plugin {
     pub id: uuid::Uuid,
     pub name: String,
@@ -281,6 +809,9 @@ pub struct Plugin {
     pub description: String,
     pub crop_types: Vec<String>,
     pub is_active: bool,
+    /// Filesystem path to the plugin's sandboxed `.wasm` module; `None` for
+    /// plugins that only ship metadata/rules implemented in-tree.
+    pub wasm_path: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
\ No newline at end of file
@@ -0,0 +1,19 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::{config::Config, database::Database, services::crop_knowledge::{CropEntry, CropKnowledgeBase}, services::ml_service::MLService};
+
+pub async fn get_crop(
+    State((database, _config, _ml_service)): State<(Database, Config, Arc<MLService>)>,
+    Path(name): Path<String>,
+) -> Result<Json<CropEntry>, StatusCode> {
+    match CropKnowledgeBase::get_crop(&database, &name).await {
+        Ok(Some(entry)) => Ok(Json(entry)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
@@ -6,16 +6,24 @@ use axum::{
 use base64::{Engine as _, engine::general_purpose};
 use chrono::Utc;
 use serde_json::json;
+use std::sync::Arc;
 
 use crate::{
     database::Database,
-    models::{DiagnosisRequest, DiagnosisResponse, Prediction, Recommendation, Priority},
+    models::{
+        BatchDiagnoseRequest, BatchDiagnoseResponse, DiagnosisRequest, DiagnosisResponse, Prediction,
+        Recommendation, Priority, TaskKind,
+    },
     config::Config,
     services::ml_service::MLService,
+    services::weather_service::{adjust_confidence_for_environment, HttpWeatherProvider, WeatherProvider},
+    services::case_memory::CaseMemoryIndex,
+    services::treatment_knowledge,
+    services::rule_engine,
 };
 
 pub async fn diagnose(
-    State((database, config): (Database, Config)),
+    State((database, config, ml_service)): State<(Database, Config, Arc<MLService>)>,
     Json(request): Json<DiagnosisRequest>,
 ) -> Result<Json<DiagnosisResponse>, StatusCode> {
     // Validate request
@@ -29,14 +37,28 @@ pub async fn diagnose(
         Err(_) => return Err(StatusCode::BAD_REQUEST),
     };
 
-    // Process image with ML service
-    let ml_predictions = match MLService::analyze_image(&image_data).await {
+    // Process image with the ML service warmed up at startup (see `main`).
+    let ml_predictions = match ml_service.analyze_image(&image_data).await {
         Ok(predictions) => predictions,
         Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
     };
 
+    // Feature vector is identical across predictions from the same
+    // inference call; grab it before `Prediction` (the API-facing type)
+    // drops it.
+    let feature_vector = ml_predictions
+        .first()
+        .and_then(|p| p.feature_vector.clone());
+
+    // Find similar past cases before converting, while we still have the
+    // raw embedding handy.
+    let similar_cases = feature_vector
+        .as_deref()
+        .map(|v| CaseMemoryIndex::query(v, 5))
+        .unwrap_or_default();
+
     // Convert ML predictions to our format
-    let predictions: Vec<Prediction> = ml_predictions
+    let mut predictions: Vec<Prediction> = ml_predictions
         .into_iter()
         .map(|p| Prediction {
             label: p.label,
@@ -46,14 +68,47 @@ pub async fn diagnose(
         })
         .collect();
 
+    // Environmental enrichment: fold in recent weather so fungal/pest
+    // confidence reflects conditions the leaf was actually photographed in.
+    let mut metadata = request.metadata.clone();
+    if let Some(ref geo) = request.geo {
+        let provider = HttpWeatherProvider::new(config.weather_api_url.clone());
+        match provider.fetch(geo).await {
+            Ok(snapshot) => {
+                for prediction in predictions.iter_mut() {
+                    prediction.confidence = adjust_confidence_for_environment(
+                        &prediction.label,
+                        &prediction.category,
+                        prediction.confidence,
+                        &snapshot,
+                    );
+                }
+                metadata = Some(merge_environmental_snapshot(metadata, &snapshot));
+            }
+            Err(e) => tracing::warn!("weather enrichment unavailable, skipping: {}", e),
+        }
+    }
+
     // Calculate overall confidence
     let confidence = predictions
         .iter()
         .map(|p| p.confidence)
         .fold(0.0, f64::max);
 
-    // Generate recommendations
+    // Generate recommendations: the treatment knowledge base first, then
+    // let the registered diagnostic rules contribute or escalate on top of
+    // it so new heuristics can ship without touching this handler.
     let recommendations = generate_recommendations(&predictions, request.crop.as_deref());
+    let rule_context = rule_engine::DiagnosisContext {
+        predictions: &predictions,
+        crop: request.crop.as_deref(),
+        metadata: metadata.as_ref(),
+        image: Some(&image_data),
+    };
+    let rule_recommendations = rule_engine::REGISTRY.run(&rule_context);
+    let mut recommendations = rule_engine::merge_recommendations(recommendations, rule_recommendations);
+    recommendations.sort_by_key(|r| std::cmp::Reverse(rule_engine::severity_rank(&r.priority)));
+    recommendations.truncate(3);
 
     // Save diagnosis to database
     let diagnosis_id = match database
@@ -63,7 +118,8 @@ pub async fn diagnose(
             &json!(predictions),
             confidence,
             request.crop.as_deref(),
-            request.metadata.as_ref(),
+            metadata.as_ref(),
+            feature_vector.as_deref(),
         )
         .await
     {
@@ -71,22 +127,133 @@ pub async fn diagnose(
         Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
     };
 
+    // Embedding the diagnosis into the case base is expensive; enqueue it
+    // rather than block the response on it.
+    if let Err(e) = database
+        .enqueue_task(&crate::models::TaskKind::EmbedDiagnosis {
+            diagnosis_id,
+        })
+        .await
+    {
+        tracing::warn!("failed to enqueue embedding task for {}: {}", diagnosis_id, e);
+    }
+
+    // Make this case findable immediately rather than waiting for the next
+    // full index rebuild.
+    if let Some(ref vector) = feature_vector {
+        let top_label = predictions
+            .first()
+            .map(|p| p.label.clone())
+            .unwrap_or_else(|| "Unknown".to_string());
+        CaseMemoryIndex::insert(diagnosis_id, top_label, vector.clone());
+    }
+
     let response = DiagnosisResponse {
         id: diagnosis_id.to_string(),
         predictions,
         confidence,
         crop_type: request.crop,
         recommendations,
+        similar_cases: similar_cases
+            .into_iter()
+            .map(|case| crate::models::SimilarCaseResponse {
+                diagnosis_id: case.diagnosis_id.to_string(),
+                label: case.label,
+                distance: case.distance as f64,
+            })
+            .collect(),
         timestamp: Utc::now(),
     };
 
     Ok(Json(response))
 }
 
+/// Enqueues a batch of leaf photos for asynchronous diagnosis and returns
+/// immediately with a task id; the `Scheduler` runs the full pipeline per
+/// image off the request path, so a bulk field survey doesn't have to hold
+/// one (10MB-limited) connection open for the whole upload.
+pub async fn batch_diagnose(
+    State((database, _config, _ml_service)): State<(Database, Config, Arc<MLService>)>,
+    Json(request): Json<BatchDiagnoseRequest>,
+) -> Result<Json<BatchDiagnoseResponse>, StatusCode> {
+    if let Err(_validation_errors) = request.validate() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let image_count = request.images_base64.len();
+
+    let task_id = database
+        .enqueue_task(&TaskKind::BatchDiagnose {
+            images_base64: request.images_base64,
+            crop: request.crop,
+        })
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(BatchDiagnoseResponse {
+        task_id: task_id.to_string(),
+        image_count,
+    }))
+}
+
+pub(crate) fn merge_environmental_snapshot(
+    metadata: Option<serde_json::Value>,
+    snapshot: &crate::services::weather_service::EnvironmentalSnapshot,
+) -> serde_json::Value {
+    let mut metadata = metadata.unwrap_or_else(|| json!({}));
+    if let Some(object) = metadata.as_object_mut() {
+        object.insert("environment".to_string(), json!(snapshot));
+    }
+    metadata
+}
+
+/// Builds a `Recommendation` straight from a matched treatment knowledge
+/// base entry, keeping the category-derived priority the generic arms
+/// already use so sorting stays consistent regardless of which path filled
+/// in the recommendation.
+fn recommendation_from_treatment(
+    label: &str,
+    category: &str,
+    treatment: &treatment_knowledge::Treatment,
+) -> Recommendation {
+    let priority = match category {
+        "Disease" => Priority::High,
+        "Pest" | "Deficiency" => Priority::Medium,
+        _ => Priority::Low,
+    };
+
+    let mut steps = treatment.chemical_remedies.clone();
+    steps.push(format!("Apply on schedule: {}", treatment.application_cadence));
+
+    Recommendation {
+        title: format!("Treat {}", label),
+        description: format!("Targeted treatment plan for {}", label),
+        priority,
+        steps,
+        safety_notes: Some(treatment.safety_notes.join("; ")),
+        organic_options: if treatment.organic_remedies.is_empty() {
+            None
+        } else {
+            Some(treatment.organic_remedies.clone())
+        },
+    }
+}
+
 fn generate_recommendations(predictions: &[Prediction], crop_type: Option<&str>) -> Vec<Recommendation> {
     let mut recommendations = Vec::new();
 
     for prediction in predictions {
+        if let Some(crop) = crop_type {
+            if let Some(treatment) = treatment_knowledge::lookup_treatment(crop, &prediction.label) {
+                recommendations.push(recommendation_from_treatment(
+                    &prediction.label,
+                    prediction.category.as_str(),
+                    treatment,
+                ));
+                continue;
+            }
+        }
+
         match prediction.category.as_str() {
             "Disease" => {
                 recommendations.push(Recommendation {
@@ -0,0 +1,74 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    database::Database,
+    models::{DumpManifest, ExportDumpResponse, TaskKind},
+    services::ml_service::MLService,
+};
+
+/// Enqueues an `ExportDump` task; the caller polls `GET /v1/diagnose/tasks/:id` for
+/// completion (same status machine every other async job uses) and then
+/// calls `download_dump` once it succeeds.
+pub async fn create_dump(
+    State((database, _config, _ml_service)): State<(Database, Config, Arc<MLService>)>,
+) -> Result<Json<ExportDumpResponse>, StatusCode> {
+    let task_id = database
+        .enqueue_task(&TaskKind::ExportDump {})
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to enqueue export dump: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(ExportDumpResponse {
+        task_id: task_id.to_string(),
+    }))
+}
+
+pub async fn download_dump(
+    State((database, _config, _ml_service)): State<(Database, Config, Arc<MLService>)>,
+    Path(id): Path<String>,
+) -> Result<Response, StatusCode> {
+    let task_id = Uuid::parse_str(&id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let task = match database.get_task(task_id).await {
+        Ok(Some(task)) => task,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    if task.status != "succeeded" {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let manifest: DumpManifest = match task.result {
+        Some(result) => serde_json::from_value(result).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+        None => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let archive = tokio::fs::read(&manifest.path).await.map_err(|e| {
+        tracing::error!("failed to read dump archive at {}: {}", manifest.path, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"dump-{}.json\"", task_id),
+            ),
+        ],
+        Bytes::from(archive),
+    )
+        .into_response())
+}
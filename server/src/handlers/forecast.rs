@@ -0,0 +1,55 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::{
+    config::Config,
+    database::Database,
+    models::{ForecastPoint, ForecastQuery, ForecastResponse, GeoLocation},
+    services::forecast_service::CombinedProvider,
+    services::ml_service::MLService,
+    utils::validate_coordinates,
+};
+
+pub async fn get_forecast(
+    State((database, config, _ml_service)): State<(Database, Config, Arc<MLService>)>,
+    Query(query): Query<ForecastQuery>,
+) -> Result<Json<ForecastResponse>, StatusCode> {
+    if validate_coordinates(query.lat, query.lon).is_err() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let requested_metrics: Vec<String> = query
+        .metrics
+        .as_deref()
+        .map(|m| m.split(',').map(|s| s.trim().to_string()).collect())
+        .unwrap_or_default();
+
+    let provider = CombinedProvider::new(config.weather_api_url.clone(), &database, 25.0);
+    let position = GeoLocation {
+        lat: query.lat,
+        lon: query.lon,
+    };
+
+    let forecast = match provider.forecast(position, &query.crop, &requested_metrics).await {
+        Ok(forecast) => forecast,
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let points = forecast
+        .into_iter()
+        .map(|(time, (risk, severity))| ForecastPoint { time, risk, severity })
+        .collect();
+
+    Ok(Json(ForecastResponse {
+        crop: query.crop,
+        center: GeoLocation {
+            lat: query.lat,
+            lon: query.lon,
+        },
+        points,
+    }))
+}
@@ -1,32 +1,51 @@
 use axum::{extract::State, http::StatusCode, Json};
 use chrono::Utc;
+use std::sync::Arc;
+use tracing::Instrument;
 
 use crate::{
     database::Database,
     models::{HealthResponse, ServiceStatus},
     config::Config,
+    services::metrics::DependencyTimer,
+    services::ml_service::MLService,
 };
 
 pub async fn health_check(
-    State((database, config): (Database, Config)),
+    State((database, config, _ml_service): (Database, Config, Arc<MLService>)),
 ) -> Result<Json<HealthResponse>, StatusCode> {
     // Check database connection
-    let db_status = match database.pool.acquire().await {
-        Ok(_) => "healthy".to_string(),
-        Err(_) => "unhealthy".to_string(),
-    };
+    let db_status = async {
+        let _timer = DependencyTimer::start("db.acquire");
+        match database.pool.acquire().await {
+            Ok(_) => "healthy".to_string(),
+            Err(_) => "unhealthy".to_string(),
+        }
+    }
+    .instrument(tracing::info_span!("health.db_acquire"))
+    .await;
 
     // Check Qdrant connection
-    let qdrant_status = match check_qdrant_health(&config.qdrant_url).await {
-        Ok(_) => "healthy".to_string(),
-        Err(_) => "unhealthy".to_string(),
-    };
+    let qdrant_status = async {
+        let _timer = DependencyTimer::start("qdrant_health");
+        match check_qdrant_health(&config.qdrant_url).await {
+            Ok(_) => "healthy".to_string(),
+            Err(_) => "unhealthy".to_string(),
+        }
+    }
+    .instrument(tracing::info_span!("health.qdrant"))
+    .await;
 
     // Check ML models
-    let ml_status = match check_ml_models().await {
-        Ok(_) => "healthy".to_string(),
-        Err(_) => "unhealthy".to_string(),
-    };
+    let ml_status = async {
+        let _timer = DependencyTimer::start("ml_models_health");
+        match check_ml_models().await {
+            Ok(_) => "healthy".to_string(),
+            Err(_) => "unhealthy".to_string(),
+        }
+    }
+    .instrument(tracing::info_span!("health.ml_models"))
+    .await;
 
     let overall_status = if db_status == "healthy" && qdrant_status == "healthy" && ml_status == "healthy" {
         "healthy"
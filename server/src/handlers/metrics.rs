@@ -0,0 +1,11 @@
+use axum::{http::header, response::IntoResponse};
+
+use crate::services::metrics::render_prometheus;
+
+/// Only mounted when `config.enable_telemetry` is true; see `create_app`.
+pub async fn metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        render_prometheus(),
+    )
+}
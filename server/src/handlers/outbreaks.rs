@@ -1,23 +1,54 @@
 use axum::{
-    extract::State,
-    http::StatusCode,
+    body::Bytes,
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
-use chrono::Utc;
+use chrono::{Duration, Utc};
+use std::sync::Arc;
 
 use crate::{
     database::Database,
     models::{
-        OutbreakReportRequest, OutbreakReportResponse, OutbreaksResponse, 
-        OutbreakData, Severity, Region
+        OutbreakReportRequest, OutbreakReportResponse, OutbreaksResponse,
+        OutbreakData, Severity, Region, OutbreakClustersResponse,
+        OutbreaksNearQuery, OutbreaksNearResponse, OutbreakNearData, GeoLocation,
+        OutbreaksHeatmapQuery, OutbreaksQuery,
     },
     config::Config,
+    handlers::diagnosis::merge_environmental_snapshot,
+    services::clustering::{cluster_outbreaks, ClusterParams},
+    services::metrics::DependencyTimer,
+    services::ml_service::MLService,
+    services::weather_service::{adjust_confidence_for_environment, HttpWeatherProvider, WeatherProvider},
+    utils::{calculate_distance, render_heatmap_tile, HeatmapPoint},
 };
 
 pub async fn report_outbreak(
-    State((database, config): (Database, Config)),
+    State((database, config, _ml_service): (Database, Config, Arc<MLService>)),
     Json(request): Json<OutbreakReportRequest>,
 ) -> Result<Json<OutbreakReportResponse>, StatusCode> {
+    // Environmental enrichment: fold in recent weather at the report's
+    // location, same as `diagnosis::diagnose`, so fungal/pest confidence on
+    // an outbreak reflects the conditions it was reported under.
+    let mut metadata = request.metadata.clone();
+    let geo = GeoLocation {
+        lat: request.latitude,
+        lon: request.longitude,
+    };
+    let provider = HttpWeatherProvider::new(config.weather_api_url.clone());
+    let confidence = match provider.fetch(&geo).await {
+        Ok(snapshot) => {
+            metadata = Some(merge_environmental_snapshot(metadata, &snapshot));
+            adjust_confidence_for_environment(&request.disease, "Disease", request.confidence, &snapshot)
+        }
+        Err(e) => {
+            tracing::warn!("weather enrichment unavailable, skipping: {}", e);
+            request.confidence
+        }
+    };
+
     // Save outbreak report to database
     let outbreak_id = match database
         .save_outbreak_report(
@@ -26,8 +57,8 @@ pub async fn report_outbreak(
             &request.disease,
             request.latitude,
             request.longitude,
-            request.confidence,
-            request.metadata.as_ref(),
+            confidence,
+            metadata.as_ref(),
         )
         .await
     {
@@ -45,7 +76,8 @@ pub async fn report_outbreak(
 }
 
 pub async fn get_outbreaks(
-    State((database, config): (Database, Config)),
+    State((database, config, _ml_service): (Database, Config, Arc<MLService>)),
+    Query(query): Query<OutbreaksQuery>,
 ) -> Result<Json<OutbreaksResponse>, StatusCode> {
     // For now, return a sample region (US bounds)
     let region = Region {
@@ -56,20 +88,54 @@ pub async fn get_outbreaks(
     };
 
     // Get outbreaks from database
-    let outbreaks = match database
-        .get_outbreaks_in_region(
-            region.min_lat,
-            region.max_lat,
-            region.min_lon,
-            region.max_lon,
-            100, // limit
-        )
-        .await
-    {
+    let outbreaks = match {
+        let _timer = DependencyTimer::start("db.get_outbreaks_in_region");
+        database
+            .get_outbreaks_in_region(
+                region.min_lat,
+                region.max_lat,
+                region.min_lon,
+                region.max_lon,
+                100, // limit
+            )
+            .await
+    } {
         Ok(outbreaks) => outbreaks,
         Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
     };
 
+    if query.clustered {
+        let defaults = ClusterParams::default();
+        let params = ClusterParams {
+            eps_km: query.eps_km.unwrap_or(defaults.eps_km),
+            min_points: query.min_points.unwrap_or(defaults.min_points),
+        };
+        let (clusters, noise) = cluster_outbreaks(&outbreaks, &params);
+
+        let noise_data: Vec<OutbreakData> = noise
+            .into_iter()
+            .map(|outbreak| OutbreakData {
+                id: outbreak.id.to_string(),
+                crop_type: outbreak.crop_type,
+                disease: outbreak.disease,
+                latitude: outbreak.latitude,
+                longitude: outbreak.longitude,
+                confidence: outbreak.confidence,
+                severity: Severity::Low,
+                reported_at: outbreak.created_at,
+            })
+            .collect();
+
+        let response = OutbreaksResponse {
+            total_count: (noise_data.len() + clusters.len()) as i64,
+            outbreaks: noise_data,
+            region,
+            clusters: Some(clusters),
+        };
+
+        return Ok(Json(response));
+    }
+
     // Convert to response format
     let outbreak_data: Vec<OutbreakData> = outbreaks
         .into_iter()
@@ -86,15 +152,171 @@ pub async fn get_outbreaks(
         .collect();
 
     let response = OutbreaksResponse {
+        total_count: outbreak_data.len() as i64,
         outbreaks: outbreak_data,
-        total_count: outbreaks.len() as i64,
         region,
+        clusters: None,
+    };
+
+    Ok(Json(response))
+}
+
+pub async fn get_outbreak_heatmap(
+    State((database, _config, _ml_service): (Database, Config, Arc<MLService>)),
+    Query(query): Query<OutbreaksHeatmapQuery>,
+) -> Result<Response, StatusCode> {
+    if query.width > crate::utils::MAX_TILE_DIMENSION || query.height > crate::utils::MAX_TILE_DIMENSION {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let region = Region {
+        min_lat: query.min_lat,
+        max_lat: query.max_lat,
+        min_lon: query.min_lon,
+        max_lon: query.max_lon,
+    };
+    let since = Utc::now() - Duration::days(query.days);
+
+    let candidates = match {
+        let _timer = DependencyTimer::start("db.get_outbreak_candidates");
+        database
+            .get_outbreak_candidates(
+                region.min_lat,
+                region.max_lat,
+                region.min_lon,
+                region.max_lon,
+                since,
+            )
+            .await
+    } {
+        Ok(candidates) => candidates,
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let points: Vec<HeatmapPoint> = candidates
+        .into_iter()
+        .filter(|report| {
+            query
+                .crop_type
+                .as_deref()
+                .map_or(true, |c| c.eq_ignore_ascii_case(&report.crop_type))
+                && query
+                    .disease
+                    .as_deref()
+                    .map_or(true, |d| d.eq_ignore_ascii_case(&report.disease))
+        })
+        .map(|report| HeatmapPoint {
+            lat: report.latitude,
+            lon: report.longitude,
+            weight: report.confidence,
+        })
+        .collect();
+
+    let png = match render_heatmap_tile(&region, query.width, query.height, &points) {
+        Ok(png) => png,
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    Ok((
+        [(header::CONTENT_TYPE, "image/png")],
+        Bytes::from(png),
+    )
+        .into_response())
+}
+
+pub async fn get_outbreaks_near(
+    State((database, _config, _ml_service): (Database, Config, Arc<MLService>)),
+    Query(query): Query<OutbreaksNearQuery>,
+) -> Result<Json<OutbreaksNearResponse>, StatusCode> {
+    if let Err(_) = crate::utils::validate_coordinates(query.lat, query.lon) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if let Err(_) = crate::utils::validate_near_query(query.radius_km, query.limit) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let outbreaks = match {
+        let _timer = DependencyTimer::start("db.get_outbreaks_near");
+        database
+            .get_outbreaks_near(query.lat, query.lon, query.radius_km, query.limit)
+            .await
+    } {
+        Ok(outbreaks) => outbreaks,
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let outbreak_data: Vec<OutbreakNearData> = outbreaks
+        .into_iter()
+        .map(|outbreak| OutbreakNearData {
+            id: outbreak.id.to_string(),
+            distance_km: calculate_distance(
+                query.lat,
+                query.lon,
+                outbreak.latitude,
+                outbreak.longitude,
+            ),
+            crop_type: outbreak.crop_type,
+            disease: outbreak.disease,
+            latitude: outbreak.latitude,
+            longitude: outbreak.longitude,
+            confidence: outbreak.confidence,
+            severity: determine_severity(outbreak.confidence),
+            reported_at: outbreak.created_at,
+        })
+        .collect();
+
+    let response = OutbreaksNearResponse {
+        total_count: outbreak_data.len() as i64,
+        outbreaks: outbreak_data,
+        center: GeoLocation {
+            lat: query.lat,
+            lon: query.lon,
+        },
+        radius_km: query.radius_km,
     };
 
     Ok(Json(response))
 }
 
-fn determine_severity(confidence: f64) -> Severity {
+pub async fn get_outbreak_clusters(
+    State((database, _config, _ml_service): (Database, Config, Arc<MLService>)),
+) -> Result<Json<OutbreakClustersResponse>, StatusCode> {
+    // Same sample region as `get_outbreaks` for now; a real deployment would
+    // take this (and the time window) as query parameters.
+    let region = Region {
+        min_lat: 24.0,
+        max_lat: 49.0,
+        min_lon: -125.0,
+        max_lon: -66.0,
+    };
+    let since = Utc::now() - Duration::days(30);
+
+    let candidates = match {
+        let _timer = DependencyTimer::start("db.get_outbreak_candidates");
+        database
+            .get_outbreak_candidates(
+                region.min_lat,
+                region.max_lat,
+                region.min_lon,
+                region.max_lon,
+                since,
+            )
+            .await
+    } {
+        Ok(candidates) => candidates,
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let (clusters, noise) = cluster_outbreaks(&candidates, &ClusterParams::default());
+
+    Ok(Json(OutbreakClustersResponse {
+        clusters,
+        noise_count: noise.len() as i64,
+        region,
+    }))
+}
+
+pub(crate) fn determine_severity(confidence: f64) -> Severity {
     if confidence >= 0.9 {
         Severity::Critical
     } else if confidence >= 0.7 {
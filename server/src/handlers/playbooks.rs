@@ -4,27 +4,80 @@ use axum::{
     Json,
 };
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::{
     database::Database,
-    models::PlaybookResponse,
+    models::{PlaybookIngestionResponse, PlaybookResponse, PlaybookStep},
     config::Config,
+    services::playbook_ingestion::{self, HttpPlaybookSource},
+    services::search_index,
+    services::ml_service::MLService,
 };
 
 pub async fn get_playbook(
-    State((database, config): (Database, Config)),
+    State((database, _config, _ml_service)): State<(Database, Config, Arc<MLService>)>,
     Path(code): Path<String>,
 ) -> Result<Json<PlaybookResponse>, StatusCode> {
-    // For now, return hardcoded playbooks
-    // In a real implementation, this would fetch from database
+    // Live, agronomist-revised protocols win when present; the hardcoded
+    // set is only a fallback for codes nobody has ingested yet.
+    match database.get_playbook_by_code(&code).await {
+        Ok(Some(record)) => return Ok(Json(playbook_from_record(record))),
+        Ok(None) => {}
+        Err(e) => {
+            tracing::warn!("failed to read playbook {} from database, falling back: {}", code, e);
+        }
+    }
+
     let playbooks = get_hardcoded_playbooks();
-    
+
     match playbooks.get(&code) {
         Some(playbook) => Ok(Json(playbook.clone())),
         None => Err(StatusCode::NOT_FOUND),
     }
 }
 
+/// Triggers an on-demand pull from the configured playbook source so
+/// agronomists can push protocol revisions without a redeploy.
+pub async fn ingest_playbooks(
+    State((database, config, _ml_service)): State<(Database, Config, Arc<MLService>)>,
+) -> Result<Json<PlaybookIngestionResponse>, StatusCode> {
+    let source = HttpPlaybookSource::new(config.playbook_source_url.clone());
+
+    let summary = playbook_ingestion::ingest_playbooks(&database, &source)
+        .await
+        .map_err(|e| {
+            tracing::error!("playbook ingestion run failed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    if let Err(e) = search_index::rebuild(&database).await {
+        tracing::warn!("failed to refresh search index after ingestion: {}", e);
+    }
+
+    Ok(Json(PlaybookIngestionResponse {
+        added: summary.added,
+        updated: summary.updated,
+        unchanged: summary.unchanged,
+        ran_at: chrono::Utc::now(),
+    }))
+}
+
+fn playbook_from_record(record: crate::database::PlaybookRecord) -> PlaybookResponse {
+    let steps: Vec<PlaybookStep> = serde_json::from_value(record.steps).unwrap_or_default();
+
+    PlaybookResponse {
+        code: record.code,
+        title: record.title,
+        description: record.description,
+        steps,
+        safety_notes: record.safety_notes,
+        organic_alternatives: record.organic_alternatives,
+        prevention_tips: record.prevention_tips,
+        last_updated: record.last_updated,
+    }
+}
+
 fn get_hardcoded_playbooks() -> HashMap<String, PlaybookResponse> {
     let mut playbooks = HashMap::new();
     
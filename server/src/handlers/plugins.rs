@@ -3,18 +3,26 @@ use axum::{
     http::StatusCode,
     Json,
 };
+use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{
     database::Database,
-    models::{PluginResponse, PluginsResponse},
+    models::{PluginResponse, PluginRunRequest, PluginRunResponse, PluginsResponse},
     config::Config,
+    services::metrics::DependencyTimer,
+    services::plugin_runtime::PluginRuntime,
+    services::rule_engine,
+    services::ml_service::MLService,
 };
 
 pub async fn list_plugins(
-    State((database, config): (Database, Config)),
+    State((database, config, _ml_service): (Database, Config, Arc<MLService>)),
 ) -> Result<Json<PluginsResponse>, StatusCode> {
-    let plugins = match database.get_plugins().await {
+    let plugins = match {
+        let _timer = DependencyTimer::start("db.get_plugins");
+        database.get_plugins().await
+    } {
         Ok(plugins) => plugins,
         Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
     };
@@ -28,11 +36,7 @@ pub async fn list_plugins(
             description: plugin.description,
             crop_types: plugin.crop_types,
             is_active: plugin.is_active,
-            features: vec![
-                "Expert diagnosis rules".to_string(),
-                "Crop-specific treatments".to_string(),
-                "Regional recommendations".to_string(),
-            ],
+            features: plugin_features(&plugin.wasm_path),
             download_url: Some(format!("/api/v1/plugins/{}/download", plugin.id)),
             created_at: plugin.created_at,
             updated_at: plugin.updated_at,
@@ -48,7 +52,7 @@ pub async fn list_plugins(
 }
 
 pub async fn get_plugin(
-    State((database, config): (Database, Config)),
+    State((database, config, _ml_service): (Database, Config, Arc<MLService>)),
     Path(id): Path<String>,
 ) -> Result<Json<PluginResponse>, StatusCode> {
     let plugin_id = match Uuid::parse_str(&id) {
@@ -56,7 +60,10 @@ pub async fn get_plugin(
         Err(_) => return Err(StatusCode::BAD_REQUEST),
     };
 
-    let plugin = match database.get_plugin(plugin_id).await {
+    let plugin = match {
+        let _timer = DependencyTimer::start("db.get_plugin");
+        database.get_plugin(plugin_id).await
+    } {
         Ok(Some(plugin)) => plugin,
         Ok(None) => return Err(StatusCode::NOT_FOUND),
         Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
@@ -69,15 +76,69 @@ pub async fn get_plugin(
         description: plugin.description,
         crop_types: plugin.crop_types,
         is_active: plugin.is_active,
-        features: vec![
-            "Expert diagnosis rules".to_string(),
-            "Crop-specific treatments".to_string(),
-            "Regional recommendations".to_string(),
-        ],
+        features: plugin_features(&plugin.wasm_path),
         download_url: Some(format!("/api/v1/plugins/{}/download", plugin.id)),
         created_at: plugin.created_at,
         updated_at: plugin.updated_at,
     };
 
     Ok(Json(response))
+}
+
+/// The `/v1/plugins` "features" a plugin row actually corresponds to.
+/// Wasm-backed plugins implement their own `diagnose` entirely independent
+/// of `rule_engine::REGISTRY`, so listing the global rule names there would
+/// be a flat-out lie about what that module does; only metadata-only
+/// plugins (no `wasm_path`) are genuinely represented by the in-tree rules.
+fn plugin_features(wasm_path: &Option<String>) -> Vec<String> {
+    if wasm_path.is_some() {
+        Vec::new()
+    } else {
+        rule_engine::REGISTRY.rule_names()
+    }
+}
+
+/// Runs a plugin's sandboxed `.wasm` module against the caller-supplied
+/// diagnosis context and returns whatever recommendations it contributes.
+/// Plugins with no `wasm_path` (metadata-only, or those whose rules already
+/// ship as a `DiagnosticRule` in-tree) have nothing to execute.
+pub async fn run_plugin(
+    State((database, _config, _ml_service)): State<(Database, Config, Arc<MLService>)>,
+    Path(id): Path<String>,
+    Json(request): Json<PluginRunRequest>,
+) -> Result<Json<PluginRunResponse>, StatusCode> {
+    let plugin_id = match Uuid::parse_str(&id) {
+        Ok(id) => id,
+        Err(_) => return Err(StatusCode::BAD_REQUEST),
+    };
+
+    let plugin = match {
+        let _timer = DependencyTimer::start("db.get_plugin");
+        database.get_plugin(plugin_id).await
+    } {
+        Ok(Some(plugin)) => plugin,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let wasm_path = plugin.wasm_path.ok_or(StatusCode::NOT_FOUND)?;
+    let wasm_bytes = tokio::fs::read(&wasm_path).await.map_err(|e| {
+        tracing::error!("failed to read plugin module at {}: {}", wasm_path, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let runtime = PluginRuntime::new().map_err(|e| {
+        tracing::error!("failed to initialize plugin runtime: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let result = runtime.run(&wasm_bytes, &request).await.map_err(|e| {
+        tracing::error!("plugin {} failed to run: {}", plugin_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(PluginRunResponse {
+        plugin_id: plugin_id.to_string(),
+        result,
+    }))
 }
\ No newline at end of file
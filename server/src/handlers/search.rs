@@ -0,0 +1,39 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::{
+    config::Config,
+    database::Database,
+    models::{SearchQuery, SearchResponse, SearchResultItem},
+    services::search_index,
+    services::ml_service::MLService,
+};
+
+pub async fn search(
+    State((_database, _config, _ml_service)): State<(Database, Config, Arc<MLService>)>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<SearchResponse>, StatusCode> {
+    if query.q.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let results = search_index::search(&query.q)
+        .into_iter()
+        .map(|hit| SearchResultItem {
+            kind: hit.kind,
+            ref_id: hit.ref_id,
+            title: hit.title,
+            snippet: hit.snippet,
+            score: hit.score,
+        })
+        .collect();
+
+    Ok(Json(SearchResponse {
+        query: query.q,
+        results,
+    }))
+}
@@ -0,0 +1,68 @@
+use axum::{extract::State, http::StatusCode, Json};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::{
+    config::Config,
+    database::Database,
+    handlers::outbreaks::determine_severity,
+    models::StatsResponse,
+    services::metrics::DependencyTimer,
+    services::ml_service::MLService,
+};
+
+/// Recent-outbreaks window used for the disease/severity breakdown; large
+/// enough to be a representative fleet snapshot without scanning the whole
+/// table on every poll (same limit `search_index` indexes over).
+const STATS_OUTBREAK_LIMIT: i64 = 5000;
+
+pub async fn get_stats(
+    State((database, _config, _ml_service)): State<(Database, Config, Arc<MLService>)>,
+) -> Result<Json<StatsResponse>, StatusCode> {
+    let outbreaks = match {
+        let _timer = DependencyTimer::start("db.get_recent_outbreaks");
+        database.get_recent_outbreaks(STATS_OUTBREAK_LIMIT).await
+    } {
+        Ok(outbreaks) => outbreaks,
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let total_outbreaks = match {
+        let _timer = DependencyTimer::start("db.count_outbreaks");
+        database.count_outbreaks().await
+    } {
+        Ok(count) => count,
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let plugins = match {
+        let _timer = DependencyTimer::start("db.get_plugins");
+        database.get_plugins().await
+    } {
+        Ok(plugins) => plugins,
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let playbooks = match {
+        let _timer = DependencyTimer::start("db.get_all_playbooks");
+        database.get_all_playbooks().await
+    } {
+        Ok(playbooks) => playbooks,
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let mut outbreaks_by_disease: HashMap<String, i64> = HashMap::new();
+    let mut outbreaks_by_severity: HashMap<crate::models::Severity, i64> = HashMap::new();
+    for outbreak in &outbreaks {
+        *outbreaks_by_disease.entry(outbreak.disease.clone()).or_insert(0) += 1;
+        *outbreaks_by_severity.entry(determine_severity(outbreak.confidence)).or_insert(0) += 1;
+    }
+
+    Ok(Json(StatsResponse {
+        total_outbreaks,
+        outbreaks_by_disease,
+        outbreaks_by_severity,
+        active_plugin_count: plugins.len() as i64,
+        playbook_count: playbooks.len() as i64,
+    }))
+}
@@ -3,16 +3,20 @@ use axum::{
     http::StatusCode,
     Json,
 };
+use std::sync::Arc;
 
 use crate::{
     database::Database,
     models::{SymptomsRequest, SymptomsResponse, PossibleCause, Recommendation, Priority},
     config::Config,
     services::symptom_service::SymptomService,
+    services::weather_service::{adjust_confidence_for_environment, HttpWeatherProvider, WeatherProvider},
+    services::treatment_knowledge,
+    services::ml_service::MLService,
 };
 
 pub async fn analyze_symptoms(
-    State((database, config): (Database, Config)),
+    State((database, config, _ml_service): (Database, Config, Arc<MLService>)),
     Json(request): Json<SymptomsRequest>,
 ) -> Result<Json<SymptomsResponse>, StatusCode> {
     // Validate request
@@ -21,15 +25,32 @@ pub async fn analyze_symptoms(
     }
 
     // Analyze symptoms using symptom service
-    let possible_causes = match SymptomService::analyze_symptoms(
+    let mut possible_causes = match SymptomService::analyze_symptoms(
         &request.crop,
         &request.symptoms,
         request.additional_info.as_deref(),
+        &config.qdrant_url,
     ).await {
         Ok(causes) => causes,
         Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
     };
 
+    // Environmental enrichment: humid, wet conditions make fungal causes
+    // more likely than the symptom text alone would suggest.
+    if let Some(ref geo) = request.geo {
+        let provider = HttpWeatherProvider::new(config.weather_api_url.clone());
+        if let Ok(snapshot) = provider.fetch(geo).await {
+            for cause in possible_causes.iter_mut() {
+                cause.confidence = adjust_confidence_for_environment(
+                    &cause.name,
+                    &cause.category,
+                    cause.confidence,
+                    &snapshot,
+                );
+            }
+        }
+    }
+
     // Generate recommendations based on possible causes
     let recommendations = generate_symptom_recommendations(&possible_causes, &request.crop);
 
@@ -48,10 +69,50 @@ pub async fn analyze_symptoms(
     Ok(Json(response))
 }
 
+/// Mirrors `diagnosis::recommendation_from_treatment` for the symptom-path
+/// wording ("Prevent X" rather than "Treat X") since a possible cause is a
+/// suspicion, not a confirmed diagnosis.
+fn recommendation_from_treatment(
+    name: &str,
+    category: &str,
+    treatment: &treatment_knowledge::Treatment,
+) -> Recommendation {
+    let priority = match category {
+        "Disease" => Priority::High,
+        "Pest" | "Deficiency" => Priority::Medium,
+        _ => Priority::Low,
+    };
+
+    let mut steps = treatment.chemical_remedies.clone();
+    steps.push(format!("Apply on schedule: {}", treatment.application_cadence));
+
+    Recommendation {
+        title: format!("Prevent {}", name),
+        description: format!("Targeted prevention plan for {}", name),
+        priority,
+        steps,
+        safety_notes: Some(treatment.safety_notes.join("; ")),
+        organic_options: if treatment.organic_remedies.is_empty() {
+            None
+        } else {
+            Some(treatment.organic_remedies.clone())
+        },
+    }
+}
+
 fn generate_symptom_recommendations(causes: &[PossibleCause], crop_type: &str) -> Vec<Recommendation> {
     let mut recommendations = Vec::new();
 
     for cause in causes {
+        if let Some(treatment) = treatment_knowledge::lookup_treatment(crop_type, &cause.name) {
+            recommendations.push(recommendation_from_treatment(
+                &cause.name,
+                cause.category.as_str(),
+                treatment,
+            ));
+            continue;
+        }
+
         match cause.category.as_str() {
             "Disease" => {
                 recommendations.push(Recommendation {
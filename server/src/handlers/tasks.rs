@@ -0,0 +1,38 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{config::Config, database::Database, models::TaskResponse, services::ml_service::MLService};
+
+pub async fn get_task(
+    State((database, _config, _ml_service)): State<(Database, Config, Arc<MLService>)>,
+    Path(id): Path<String>,
+) -> Result<Json<TaskResponse>, StatusCode> {
+    let task_id = match Uuid::parse_str(&id) {
+        Ok(id) => id,
+        Err(_) => return Err(StatusCode::BAD_REQUEST),
+    };
+
+    let task = match database.get_task(task_id).await {
+        Ok(Some(task)) => task,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let status = task.status.parse().map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(TaskResponse {
+        id: task.id.to_string(),
+        kind: task.kind,
+        status,
+        retry_count: task.retry_count,
+        error: task.error,
+        result: task.result,
+        created_at: task.created_at,
+        updated_at: task.updated_at,
+    }))
+}
@@ -0,0 +1,14 @@
+use axum::{extract::State, Json};
+use std::sync::Arc;
+
+use crate::{config::Config, database::Database, models::VersionResponse, services::ml_service::MLService};
+
+pub async fn get_version(
+    State((_database, config, _ml_service)): State<(Database, Config, Arc<MLService>)>,
+) -> Json<VersionResponse> {
+    Json(VersionResponse {
+        model_version: config.model_version,
+        region_code: config.region_code,
+        build_version: env!("CARGO_PKG_VERSION").to_string(),
+    })
+}
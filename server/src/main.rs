@@ -6,6 +6,7 @@ use axum::{
     Router,
 };
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tower::ServiceBuilder;
 use tower_http::{
     cors::{Any, CorsLayer},
@@ -22,20 +23,27 @@ mod utils;
 
 use config::Config;
 use database::Database;
+use services::ml_service::MLService;
+use services::scheduler::Scheduler;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Initialize tracing
+    // Load configuration first so the tracing filter can be driven by
+    // `config.log_level`; `RUST_LOG` still wins when set, for local
+    // overrides without touching the environment's LOG_LEVEL.
+    let config = Config::load()?;
+
     tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "leaflens_server=debug,tower_http=debug".into()),
-        )
+        .with(tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+            format!(
+                "leaflens_server={level},tower_http={level}",
+                level = config.log_level
+            )
+            .into()
+        }))
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    // Load configuration
-    let config = Config::load()?;
     tracing::info!("Configuration loaded successfully");
 
     // Initialize database
@@ -46,8 +54,43 @@ async fn main() -> anyhow::Result<()> {
     database.run_migrations().await?;
     tracing::info!("Database migrations completed");
 
+    // Warm the crop knowledge base cache
+    if let Err(e) = services::crop_knowledge::CropKnowledgeBase::refresh(&database).await {
+        tracing::warn!("failed to warm crop knowledge base cache: {}", e);
+    }
+
+    // Build the full-text search index over ingested playbooks and
+    // outbreak reports; re-run whenever playbook ingestion completes.
+    if let Err(e) = services::search_index::rebuild(&database).await {
+        tracing::warn!("failed to build search index: {}", e);
+    }
+
+    // Load the classifier/segmentation backend (local ONNX or remote
+    // cluster dispatch, per `config.inference_backend`) once at startup
+    // and share the warmed-up service across every request; constructing
+    // a fresh, uninitialized `MLService` per call would always fall back
+    // to mock predictions.
+    let mut ml_service = MLService::new().with_temperature(config.ml_temperature);
+    if let Err(e) = ml_service.initialize(&config).await {
+        tracing::warn!("failed to initialize ML service, falling back to mock predictions: {}", e);
+    }
+    let ml_service = Arc::new(ml_service);
+
+    // Start the background task scheduler (embedding, batch diagnosis,
+    // export dumps) so these run off the request path. Cluster
+    // recomputation and image reprocessing are not scheduler tasks.
+    tokio::spawn(
+        Scheduler::new(
+            database.clone(),
+            config.scheduler_max_concurrency,
+            config.dump_dir.clone(),
+            ml_service.clone(),
+        )
+        .run(),
+    );
+
     // Build application
-    let app = create_app(database, config).await?;
+    let app = create_app(database, config, ml_service).await?;
 
     // Start server
     let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
@@ -59,28 +102,49 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-async fn create_app(database: Database, config: Config) -> anyhow::Result<Router> {
+async fn create_app(database: Database, config: Config, ml_service: Arc<MLService>) -> anyhow::Result<Router> {
     let cors = CorsLayer::new()
         .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
         .allow_headers(Any)
         .allow_origin(Any);
 
-    let app = Router::new()
+    let enable_telemetry = config.enable_telemetry;
+
+    let mut app = Router::new()
         .route("/health", get(handlers::health::health_check))
         .route("/v1/diagnose", post(handlers::diagnosis::diagnose))
+        .route("/v1/diagnose/batch", post(handlers::diagnosis::batch_diagnose))
+        .route("/v1/diagnose/tasks/:id", get(handlers::tasks::get_task))
         .route("/v1/symptoms", post(handlers::symptoms::analyze_symptoms))
         .route("/v1/playbooks/:code", get(handlers::playbooks::get_playbook))
+        .route("/v1/admin/playbooks/ingest", post(handlers::playbooks::ingest_playbooks))
+        .route("/v1/search", get(handlers::search::search))
+        .route("/v1/version", get(handlers::version::get_version))
+        .route("/v1/stats", get(handlers::stats::get_stats))
+        .route("/v1/admin/dumps", post(handlers::dumps::create_dump))
+        .route("/v1/admin/dumps/:id/download", get(handlers::dumps::download_dump))
         .route("/v1/outbreaks", get(handlers::outbreaks::get_outbreaks))
         .route("/v1/outbreaks", post(handlers::outbreaks::report_outbreak))
+        .route("/v1/outbreaks/clusters", get(handlers::outbreaks::get_outbreak_clusters))
+        .route("/v1/outbreaks/near", get(handlers::outbreaks::get_outbreaks_near))
+        .route("/v1/outbreaks/heatmap", get(handlers::outbreaks::get_outbreak_heatmap))
         .route("/v1/plugins", get(handlers::plugins::list_plugins))
         .route("/v1/plugins/:id", get(handlers::plugins::get_plugin))
+        .route("/v1/plugins/:id/run", post(handlers::plugins::run_plugin))
+        .route("/crops/:name", get(handlers::crops::get_crop))
+        .route("/v1/forecast", get(handlers::forecast::get_forecast))
         .layer(
             ServiceBuilder::new()
                 .layer(TraceLayer::new_for_http())
+                .layer(middleware::from_fn(services::metrics::track_metrics))
                 .layer(cors)
                 .layer(DefaultBodyLimit::max(10 * 1024 * 1024)), // 10MB limit
         )
-        .with_state((database, config));
+        .with_state((database, config, ml_service));
+
+    if enable_telemetry {
+        app = app.route("/metrics", get(handlers::metrics::metrics));
+    }
 
     Ok(app)
 }
\ No newline at end of file
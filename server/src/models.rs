@@ -23,9 +23,17 @@ pub struct DiagnosisResponse {
     pub confidence: f64,
     pub crop_type: Option<String>,
     pub recommendations: Vec<Recommendation>,
+    pub similar_cases: Vec<SimilarCaseResponse>,
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SimilarCaseResponse {
+    pub diagnosis_id: String,
+    pub label: String,
+    pub distance: f64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Prediction {
     pub label: String,
@@ -100,6 +108,82 @@ pub struct PlaybookStep {
     pub warnings: Option<Vec<String>>,
 }
 
+/// Per-run counts from `services::playbook_ingestion`, returned by the
+/// admin ingestion endpoint so agronomists can confirm a revision actually
+/// landed (and how many documents were untouched).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlaybookIngestionResponse {
+    pub added: i64,
+    pub updated: i64,
+    pub unchanged: i64,
+    pub ran_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub query: String,
+    pub results: Vec<SearchResultItem>,
+}
+
+/// A single full-text match, tagged with which record type it came from
+/// so the client can route to the right detail view.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchResultItem {
+    pub kind: String,
+    pub ref_id: String,
+    pub title: String,
+    pub snippet: String,
+    pub score: f32,
+}
+
+/// Build/identity info for `GET /v1/version`, separate from `HealthResponse`
+/// since operators poll this for fleet inventory rather than liveness.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VersionResponse {
+    pub model_version: String,
+    pub region_code: String,
+    pub build_version: String,
+}
+
+/// At-a-glance fleet counters for `GET /v1/stats`; severity buckets are
+/// computed with the same `determine_severity` thresholds the non-clustered
+/// `/v1/outbreaks` response uses, so a report lands in the same severity
+/// here as it does there. The disease/severity breakdown covers the most
+/// recent reports fleet-wide, not the region and limit `/v1/outbreaks` uses.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatsResponse {
+    pub total_outbreaks: i64,
+    pub outbreaks_by_disease: std::collections::HashMap<String, i64>,
+    pub outbreaks_by_severity: std::collections::HashMap<Severity, i64>,
+    pub active_plugin_count: i64,
+    pub playbook_count: i64,
+}
+
+/// Returned by `POST /v1/admin/dumps`: the caller polls `GET /v1/diagnose/tasks/:id`
+/// (the same generic task-status endpoint the other async jobs use) until
+/// `status` is `succeeded`, then downloads the archive.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportDumpResponse {
+    pub task_id: String,
+}
+
+/// Stashed as the `ExportDump` task's `result` once the archive is written,
+/// so the download handler knows where to find it without re-deriving the
+/// path from the task id.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DumpManifest {
+    pub path: String,
+    pub outbreak_count: i64,
+    pub playbook_count: i64,
+    pub plugin_count: i64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OutbreakReportRequest {
     pub crop_type: String,
@@ -122,6 +206,19 @@ pub struct OutbreaksResponse {
     pub outbreaks: Vec<OutbreakData>,
     pub total_count: i64,
     pub region: Region,
+    /// Populated only when `?clustered=true` was requested: density-based
+    /// hotspots grouped from `outbreaks`, which in that mode holds only the
+    /// points too sparse to join a cluster.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clusters: Option<Vec<OutbreakCluster>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OutbreaksQuery {
+    #[serde(default)]
+    pub clustered: bool,
+    pub eps_km: Option<f64>,
+    pub min_points: Option<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -136,7 +233,7 @@ pub struct OutbreakData {
     pub reported_at: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Severity {
     Low,
     Medium,
@@ -152,6 +249,88 @@ pub struct Region {
     pub max_lon: f64,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct OutbreaksHeatmapQuery {
+    pub min_lat: f64,
+    pub max_lat: f64,
+    pub min_lon: f64,
+    pub max_lon: f64,
+    #[serde(default = "default_tile_dimension")]
+    pub width: u32,
+    #[serde(default = "default_tile_dimension")]
+    pub height: u32,
+    pub crop_type: Option<String>,
+    pub disease: Option<String>,
+    #[serde(default = "default_heatmap_window_days")]
+    pub days: i64,
+}
+
+fn default_tile_dimension() -> u32 {
+    256
+}
+
+fn default_heatmap_window_days() -> i64 {
+    30
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OutbreaksNearQuery {
+    pub lat: f64,
+    pub lon: f64,
+    #[serde(default = "default_radius_km")]
+    pub radius_km: f64,
+    #[serde(default = "default_near_limit")]
+    pub limit: i64,
+}
+
+fn default_radius_km() -> f64 {
+    10.0
+}
+
+fn default_near_limit() -> i64 {
+    50
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OutbreaksNearResponse {
+    pub outbreaks: Vec<OutbreakNearData>,
+    pub total_count: i64,
+    pub center: GeoLocation,
+    pub radius_km: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OutbreakNearData {
+    pub id: String,
+    pub crop_type: String,
+    pub disease: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub confidence: f64,
+    pub severity: Severity,
+    pub distance_km: f64,
+    pub reported_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OutbreakClustersResponse {
+    pub clusters: Vec<OutbreakCluster>,
+    pub noise_count: i64,
+    pub region: Region,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OutbreakCluster {
+    pub crop_type: String,
+    pub disease: String,
+    pub centroid_lat: f64,
+    pub centroid_lon: f64,
+    pub member_count: i64,
+    pub bounding_box: Region,
+    pub confidence: f64,
+    pub severity: Severity,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PluginResponse {
     pub id: String,
@@ -172,6 +351,38 @@ pub struct PluginsResponse {
     pub total_count: i64,
 }
 
+/// Diagnosis context handed to a plugin's sandboxed `diagnose` export, so it
+/// can contribute recommendations/overrides without seeing the raw image.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginRunRequest {
+    pub crop_type: Option<String>,
+    pub region_code: String,
+    pub candidates: Vec<PluginDiseaseCandidate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginDiseaseCandidate {
+    pub label: String,
+    pub category: String,
+    pub confidence: f64,
+}
+
+/// Decoded straight from the guest's JSON result. Plugins are trusted to
+/// emit well-formed `Recommendation`s; anything else in the payload is
+/// ignored rather than rejected, so a plugin can add fields in a newer
+/// version without breaking older hosts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PluginRunResult {
+    #[serde(default)]
+    pub recommendations: Vec<Recommendation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PluginRunResponse {
+    pub plugin_id: String,
+    pub result: PluginRunResult,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct HealthResponse {
     pub status: String,
@@ -187,6 +398,129 @@ pub struct ServiceStatus {
     pub ml_models: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Enqueued,
+    Processing,
+    Succeeded,
+    Failed,
+}
+
+impl TaskStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskStatus::Enqueued => "enqueued",
+            TaskStatus::Processing => "processing",
+            TaskStatus::Succeeded => "succeeded",
+            TaskStatus::Failed => "failed",
+        }
+    }
+}
+
+impl std::str::FromStr for TaskStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "enqueued" => Ok(TaskStatus::Enqueued),
+            "processing" => Ok(TaskStatus::Processing),
+            "succeeded" => Ok(TaskStatus::Succeeded),
+            "failed" => Ok(TaskStatus::Failed),
+            other => Err(anyhow::anyhow!("unknown task status: {}", other)),
+        }
+    }
+}
+
+/// A job the `Scheduler` can dequeue and run, tagged with the content it
+/// needs to do so (hence "kind with content" rather than a bare enum).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TaskKind {
+    EmbedDiagnosis { diagnosis_id: uuid::Uuid },
+    BatchDiagnose {
+        images_base64: Vec<String>,
+        crop: Option<String>,
+    },
+    /// Serializes outbreaks, playbooks, and the plugin registry to a
+    /// versioned archive on disk; see `services::scheduler::run_export_dump`.
+    ExportDump {},
+}
+
+impl TaskKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TaskKind::EmbedDiagnosis { .. } => "embed_diagnosis",
+            TaskKind::BatchDiagnose { .. } => "batch_diagnose",
+            TaskKind::ExportDump { .. } => "export_dump",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskResponse {
+    pub id: String,
+    pub kind: String,
+    pub status: TaskStatus,
+    pub retry_count: i32,
+    pub error: Option<String>,
+    pub result: Option<serde_json::Value>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Submits a batch of leaf photos for asynchronous diagnosis; the full
+/// decode-segment-classify pipeline runs off the request path via the
+/// `Scheduler`, one task per batch.
+#[derive(Debug, Deserialize, Validate)]
+pub struct BatchDiagnoseRequest {
+    #[validate(length(min = 1, max = 50))]
+    pub images_base64: Vec<String>,
+    pub crop: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchDiagnoseResponse {
+    pub task_id: String,
+    pub image_count: usize,
+}
+
+/// One image's outcome inside a finished `BatchDiagnose` task's `result`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchDiagnosisResult {
+    pub image_index: usize,
+    pub predictions: Vec<Prediction>,
+    pub confidence: f64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ForecastQuery {
+    pub lat: f64,
+    pub lon: f64,
+    pub crop: String,
+    /// Comma-separated list of disease names to include; all relevant
+    /// diseases for the crop are returned when omitted.
+    pub metrics: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForecastResponse {
+    pub crop: String,
+    pub center: GeoLocation,
+    pub points: Vec<ForecastPoint>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForecastPoint {
+    pub time: chrono::DateTime<chrono::Utc>,
+    pub risk: std::collections::HashMap<String, f64>,
+    /// Per-disease `risk` graded into `Severity`, so callers get
+    /// "high bacterial-spot risk in 2 days" without picking their own
+    /// thresholds on the raw score.
+    pub severity: std::collections::HashMap<String, Severity>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ErrorResponse {
     pub error: String,
@@ -0,0 +1,212 @@
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use rand::Rng;
+use uuid::Uuid;
+
+use crate::database::Database;
+
+const NUM_TREES: usize = 8;
+const LEAF_SIZE: usize = 10;
+const CANDIDATE_LIMIT: usize = 200;
+
+/// A match surfaced alongside a fresh diagnosis: an earlier case with a
+/// similar feature embedding.
+#[derive(Debug, Clone)]
+pub struct SimilarCase {
+    pub diagnosis_id: Uuid,
+    pub label: String,
+    pub distance: f32,
+}
+
+enum Node {
+    Leaf(Vec<usize>),
+    Split {
+        normal: Vec<f32>,
+        offset: f32,
+        left: Box<Node>,
+        right: Box<Node>,
+    },
+}
+
+/// Approximate-nearest-neighbour index over diagnosis feature vectors,
+/// modeled on a random-projection forest (as used by e.g. Meilisearch's
+/// `arroy`): each tree recursively splits its points with a random
+/// hyperplane between two sampled points; a query descends every tree to
+/// gather a candidate set, which is then reranked by exact cosine
+/// distance.
+struct Forest {
+    ids: Vec<Uuid>,
+    labels: Vec<String>,
+    vectors: Vec<Vec<f32>>,
+    trees: Vec<Node>,
+    /// Number of leading `vectors` the trees were built over; anything
+    /// past this was added by `insert` since the last rebuild.
+    indexed: usize,
+}
+
+impl Forest {
+    fn empty() -> Self {
+        Self {
+            ids: Vec::new(),
+            labels: Vec::new(),
+            vectors: Vec::new(),
+            trees: Vec::new(),
+            indexed: 0,
+        }
+    }
+}
+
+static INDEX: Lazy<RwLock<Forest>> = Lazy::new(|| RwLock::new(Forest::empty()));
+
+pub struct CaseMemoryIndex;
+
+impl CaseMemoryIndex {
+    /// Rebuilds the forest from the database. Cheap enough to call from a
+    /// background task; not on the request path.
+    pub async fn rebuild(database: &Database) -> anyhow::Result<()> {
+        let records = database.get_diagnosis_embeddings(5000).await?;
+
+        let mut forest = Forest::empty();
+        for (id, vector, label) in records {
+            forest.ids.push(id);
+            forest.labels.push(label);
+            forest.vectors.push(vector);
+        }
+
+        let mut rng = rand::thread_rng();
+        let all_indices: Vec<usize> = (0..forest.vectors.len()).collect();
+        forest.trees = (0..NUM_TREES)
+            .map(|_| build_tree(&forest.vectors, &all_indices, &mut rng))
+            .collect();
+        forest.indexed = forest.vectors.len();
+
+        *INDEX.write().unwrap() = forest;
+        Ok(())
+    }
+
+    /// Inserts a single new vector into the live index without a full
+    /// rebuild, so newly saved diagnoses are matchable immediately.
+    pub fn insert(id: Uuid, label: String, vector: Vec<f32>) {
+        let mut index = INDEX.write().unwrap();
+        index.ids.push(id);
+        index.labels.push(label);
+        index.vectors.push(vector);
+        // Leave existing trees as-is; they don't contain this point until
+        // the next `rebuild`, so `query` scans every point inserted since
+        // then exactly, alongside the tree candidates.
+    }
+
+    pub fn query(vector: &[f32], top_k: usize) -> Vec<SimilarCase> {
+        let index = INDEX.read().unwrap();
+        if index.vectors.is_empty() {
+            return Vec::new();
+        }
+
+        let mut candidates = std::collections::HashSet::new();
+        for tree in &index.trees {
+            collect_leaf(tree, vector, &mut candidates);
+            if candidates.len() >= CANDIDATE_LIMIT {
+                break;
+            }
+        }
+        // Points inserted since the last rebuild aren't in any tree.
+        candidates.extend(index.indexed..index.vectors.len());
+
+        // Forest may be empty right after a cold start with no trees yet
+        // built; fall back to scanning everything.
+        if candidates.is_empty() {
+            candidates.extend(0..index.vectors.len());
+        }
+
+        let mut scored: Vec<(usize, f32)> = candidates
+            .into_iter()
+            .map(|i| (i, cosine_distance(vector, &index.vectors[i])))
+            .collect();
+        scored.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        scored.truncate(top_k);
+
+        scored
+            .into_iter()
+            .map(|(i, distance)| SimilarCase {
+                diagnosis_id: index.ids[i],
+                label: index.labels[i].clone(),
+                distance,
+            })
+            .collect()
+    }
+}
+
+fn build_tree(vectors: &[Vec<f32>], indices: &[usize], rng: &mut impl Rng) -> Node {
+    if indices.len() <= LEAF_SIZE {
+        return Node::Leaf(indices.to_vec());
+    }
+
+    let a = indices[rng.gen_range(0..indices.len())];
+    let mut b = indices[rng.gen_range(0..indices.len())];
+    let mut guard = 0;
+    while b == a && guard < 10 {
+        b = indices[rng.gen_range(0..indices.len())];
+        guard += 1;
+    }
+
+    let normal: Vec<f32> = vectors[a]
+        .iter()
+        .zip(vectors[b].iter())
+        .map(|(x, y)| x - y)
+        .collect();
+    let midpoint: Vec<f32> = vectors[a]
+        .iter()
+        .zip(vectors[b].iter())
+        .map(|(x, y)| (x + y) / 2.0)
+        .collect();
+    let offset = dot(&normal, &midpoint);
+
+    let (mut left, mut right) = (Vec::new(), Vec::new());
+    for &i in indices {
+        if dot(&normal, &vectors[i]) - offset <= 0.0 {
+            left.push(i);
+        } else {
+            right.push(i);
+        }
+    }
+
+    // A degenerate split (everything on one side) would recurse forever;
+    // just stop here and treat the remainder as a leaf.
+    if left.is_empty() || right.is_empty() {
+        return Node::Leaf(indices.to_vec());
+    }
+
+    Node::Split {
+        offset,
+        left: Box::new(build_tree(vectors, &left, rng)),
+        right: Box::new(build_tree(vectors, &right, rng)),
+        normal,
+    }
+}
+
+fn collect_leaf(node: &Node, query: &[f32], out: &mut std::collections::HashSet<usize>) {
+    match node {
+        Node::Leaf(indices) => out.extend(indices.iter().copied()),
+        Node::Split { normal, offset, left, right } => {
+            if dot(normal, query) - offset <= 0.0 {
+                collect_leaf(left, query, out);
+            } else {
+                collect_leaf(right, query, out);
+            }
+        }
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+fn cosine_distance(a: &[f32], b: &[f32]) -> f32 {
+    let norm_a = dot(a, a).sqrt();
+    let norm_b = dot(b, b).sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 1.0;
+    }
+    1.0 - (dot(a, b) / (norm_a * norm_b))
+}
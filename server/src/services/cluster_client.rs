@@ -0,0 +1,153 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use ndarray::Array3;
+use serde::{Deserialize, Serialize};
+
+/// A worker process registered with the broker, discovered via periodic
+/// heartbeats. Workers past `HEARTBEAT_STALE_SECS` are treated as down and
+/// skipped by dispatch.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkerInfo {
+    pub id: String,
+    pub address: String,
+    pub last_heartbeat: DateTime<Utc>,
+}
+
+const HEARTBEAT_STALE_SECS: i64 = 30;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum InferenceOp {
+    Classify,
+    Segment,
+}
+
+#[derive(Debug, Serialize)]
+struct InferenceJob {
+    request_id: String,
+    op: InferenceOp,
+    shape: [usize; 3],
+    tensor: Vec<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InferenceReply {
+    #[allow(dead_code)]
+    request_id: String,
+    probabilities: Option<Vec<f64>>,
+    feature_vector: Option<Vec<f32>>,
+    tensor: Option<Vec<f32>>,
+    error: Option<String>,
+}
+
+/// Talks to a pool of remote ONNX worker processes over the cluster
+/// message bus. Modeled here as request/reply HTTP against the broker
+/// (matching how `weather_service`/`symptom_service` reach external
+/// services over plain `reqwest` rather than a dedicated MQ client crate):
+/// the broker tracks worker heartbeats for discovery, and a job is
+/// published to whichever worker round-robin dispatch currently picks.
+/// Workers subscribe, run `classify_image`/`segment_leaf` themselves, and
+/// reply with the result keyed by `request_id`.
+#[derive(Clone)]
+pub struct ClusterClient {
+    broker_url: String,
+    timeout: Duration,
+    next_worker: Arc<AtomicUsize>,
+    http: reqwest::Client,
+}
+
+impl ClusterClient {
+    pub fn new(broker_url: String, timeout: Duration) -> Self {
+        Self {
+            broker_url,
+            timeout,
+            next_worker: Arc::new(AtomicUsize::new(0)),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub async fn classify(
+        &self,
+        request_id: &str,
+        tensor: &Array3<f32>,
+    ) -> Result<(Vec<f64>, Option<Vec<f32>>)> {
+        let reply = self.dispatch(request_id, InferenceOp::Classify, tensor).await?;
+        let probabilities = reply
+            .probabilities
+            .ok_or_else(|| anyhow!("worker reply for {} had no probabilities", request_id))?;
+        Ok((probabilities, reply.feature_vector))
+    }
+
+    pub async fn segment(&self, request_id: &str, tensor: &Array3<f32>) -> Result<Array3<f32>> {
+        let shape = tensor.dim();
+        let reply = self.dispatch(request_id, InferenceOp::Segment, tensor).await?;
+        let flat = reply
+            .tensor
+            .ok_or_else(|| anyhow!("worker reply for {} had no tensor", request_id))?;
+        Array3::from_shape_vec(shape, flat)
+            .map_err(|e| anyhow!("malformed tensor from worker: {}", e))
+    }
+
+    /// Round-robins across workers whose last heartbeat is still fresh.
+    async fn pick_worker(&self) -> Result<String> {
+        let workers: Vec<WorkerInfo> = self
+            .http
+            .get(format!("{}/workers", self.broker_url))
+            .timeout(self.timeout)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let healthy: Vec<&WorkerInfo> = workers
+            .iter()
+            .filter(|w| Utc::now().signed_duration_since(w.last_heartbeat).num_seconds() < HEARTBEAT_STALE_SECS)
+            .collect();
+
+        if healthy.is_empty() {
+            return Err(anyhow!("no healthy cluster workers registered with broker"));
+        }
+
+        let index = self.next_worker.fetch_add(1, Ordering::Relaxed) % healthy.len();
+        Ok(healthy[index].address.clone())
+    }
+
+    async fn dispatch(
+        &self,
+        request_id: &str,
+        op: InferenceOp,
+        tensor: &Array3<f32>,
+    ) -> Result<InferenceReply> {
+        let worker_address = self.pick_worker().await?;
+        let (h, w, c) = tensor.dim();
+
+        let job = InferenceJob {
+            request_id: request_id.to_string(),
+            op,
+            shape: [h, w, c],
+            tensor: tensor.iter().copied().collect(),
+        };
+
+        let reply: InferenceReply = self
+            .http
+            .post(format!("{}/jobs", worker_address))
+            .json(&job)
+            .timeout(self.timeout)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if let Some(error) = &reply.error {
+            return Err(anyhow!("worker {} reported error: {}", worker_address, error));
+        }
+
+        Ok(reply)
+    }
+}
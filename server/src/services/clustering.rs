@@ -0,0 +1,172 @@
+use crate::database::OutbreakReport;
+use crate::models::{OutbreakCluster, Region, Severity};
+use crate::utils::{calculate_distance, get_severity_level};
+
+/// DBSCAN clustering of outbreak reports into hotspots, grouped by
+/// `(crop_type, disease)` and using the haversine distance as the metric.
+pub struct ClusterParams {
+    pub eps_km: f64,
+    pub min_points: usize,
+}
+
+impl Default for ClusterParams {
+    fn default() -> Self {
+        Self {
+            eps_km: 25.0,
+            min_points: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Label {
+    Unvisited,
+    Noise,
+    Cluster(usize),
+}
+
+/// Groups `reports` into density-based clusters per crop+disease and
+/// returns the resulting hotspots plus the individual reports left as
+/// noise (too sparse to form a cluster of their own).
+pub fn cluster_outbreaks(
+    reports: &[OutbreakReport],
+    params: &ClusterParams,
+) -> (Vec<OutbreakCluster>, Vec<OutbreakReport>) {
+    let mut clusters = Vec::new();
+    let mut noise = Vec::new();
+
+    let mut groups: std::collections::HashMap<(String, String), Vec<&OutbreakReport>> =
+        std::collections::HashMap::new();
+    for report in reports {
+        groups
+            .entry((report.crop_type.clone(), report.disease.clone()))
+            .or_default()
+            .push(report);
+    }
+
+    for ((crop_type, disease), points) in groups {
+        let (mut group_clusters, mut group_noise) = dbscan(&points, params);
+        clusters.append(&mut group_clusters);
+        noise.append(&mut group_noise);
+        let _ = (&crop_type, &disease); // crop_type/disease are already carried on each cluster
+    }
+
+    (clusters, noise)
+}
+
+fn neighbors(points: &[&OutbreakReport], idx: usize, eps_km: f64) -> Vec<usize> {
+    let origin = points[idx];
+    points
+        .iter()
+        .enumerate()
+        .filter(|(i, p)| {
+            *i != idx
+                && calculate_distance(origin.latitude, origin.longitude, p.latitude, p.longitude)
+                    <= eps_km
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+fn dbscan(points: &[&OutbreakReport], params: &ClusterParams) -> (Vec<OutbreakCluster>, Vec<OutbreakReport>) {
+    let mut labels = vec![Label::Unvisited; points.len()];
+    let mut cluster_id = 0usize;
+
+    for i in 0..points.len() {
+        if labels[i] != Label::Unvisited {
+            continue;
+        }
+
+        let mut seeds = neighbors(points, i, params.eps_km);
+        if seeds.len() < params.min_points {
+            labels[i] = Label::Noise;
+            continue;
+        }
+
+        labels[i] = Label::Cluster(cluster_id);
+        let mut j = 0;
+        while j < seeds.len() {
+            let q = seeds[j];
+            if labels[q] == Label::Noise {
+                labels[q] = Label::Cluster(cluster_id);
+            }
+            if labels[q] == Label::Unvisited {
+                labels[q] = Label::Cluster(cluster_id);
+                let q_neighbors = neighbors(points, q, params.eps_km);
+                if q_neighbors.len() >= params.min_points {
+                    for n in q_neighbors {
+                        if !seeds.contains(&n) {
+                            seeds.push(n);
+                        }
+                    }
+                }
+            }
+            j += 1;
+        }
+
+        cluster_id += 1;
+    }
+
+    let noise: Vec<OutbreakReport> = points
+        .iter()
+        .zip(labels.iter())
+        .filter(|(_, l)| **l == Label::Noise)
+        .map(|(p, _)| (*p).clone())
+        .collect();
+
+    let mut clusters = Vec::new();
+    for id in 0..cluster_id {
+        let members: Vec<&&OutbreakReport> = points
+            .iter()
+            .zip(labels.iter())
+            .filter(|(_, l)| **l == Label::Cluster(id))
+            .map(|(p, _)| p)
+            .collect();
+
+        if members.is_empty() {
+            continue;
+        }
+
+        let member_count = members.len();
+        let centroid_lat =
+            members.iter().map(|m| m.latitude).sum::<f64>() / member_count as f64;
+        let centroid_lon =
+            members.iter().map(|m| m.longitude).sum::<f64>() / member_count as f64;
+        let mean_confidence =
+            members.iter().map(|m| m.confidence).sum::<f64>() / member_count as f64;
+
+        let bounding_box = Region {
+            min_lat: members.iter().map(|m| m.latitude).fold(f64::MAX, f64::min),
+            max_lat: members.iter().map(|m| m.latitude).fold(f64::MIN, f64::max),
+            min_lon: members.iter().map(|m| m.longitude).fold(f64::MAX, f64::min),
+            max_lon: members.iter().map(|m| m.longitude).fold(f64::MIN, f64::max),
+        };
+
+        let severity = match get_severity_level(mean_confidence * severity_boost(member_count)) {
+            "Critical" => Severity::Critical,
+            "High" => Severity::High,
+            "Medium" => Severity::Medium,
+            _ => Severity::Low,
+        };
+
+        clusters.push(OutbreakCluster {
+            crop_type: members[0].crop_type.clone(),
+            disease: members[0].disease.clone(),
+            centroid_lat,
+            centroid_lon,
+            member_count: member_count as i64,
+            bounding_box,
+            confidence: mean_confidence,
+            severity,
+        });
+    }
+
+    (clusters, noise)
+}
+
+/// Larger clusters are more likely to represent a genuine outbreak rather
+/// than a few coincidentally nearby reports, so we nudge the severity
+/// derived from mean confidence upward with cluster size.
+fn severity_boost(member_count: usize) -> f64 {
+    (1.0 + (member_count as f64).ln() * 0.05).min(1.2)
+}
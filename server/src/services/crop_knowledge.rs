@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::database::Database;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CropDisease {
+    pub name: String,
+    pub symptoms: Vec<String>,
+    pub organic_controls: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CropEntry {
+    pub canonical_name: String,
+    pub synonyms: Vec<String>,
+    pub botanical_name: String,
+    pub family: String,
+    pub habitat: String,
+    pub diseases: Vec<CropDisease>,
+}
+
+/// Process-wide cache of the `crops` table, keyed by every lowercased
+/// name a crop is known by (canonical name plus synonyms) so lookups
+/// don't need to scan. Replaces the compile-time `VALID_CROPS` list and
+/// synonym map that used to live in `utils`.
+static CACHE: Lazy<RwLock<HashMap<String, CropEntry>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+pub struct CropKnowledgeBase;
+
+impl CropKnowledgeBase {
+    /// Reloads the cache from the database. Call this at startup and
+    /// whenever crop rows change; lookups fall back to an on-demand
+    /// refresh if the cache is empty.
+    pub async fn refresh(database: &Database) -> anyhow::Result<()> {
+        let records = database.get_crops().await?;
+        let mut entries = HashMap::new();
+
+        for record in records {
+            let diseases: Vec<CropDisease> =
+                serde_json::from_value(record.diseases).unwrap_or_default();
+
+            let entry = CropEntry {
+                canonical_name: record.canonical_name.clone(),
+                synonyms: record.synonyms.clone(),
+                botanical_name: record.botanical_name,
+                family: record.family,
+                habitat: record.habitat,
+                diseases,
+            };
+
+            entries.insert(record.canonical_name.to_lowercase(), entry.clone());
+            for synonym in &record.synonyms {
+                entries.insert(synonym.to_lowercase(), entry.clone());
+            }
+        }
+
+        *CACHE.write().unwrap() = entries;
+        Ok(())
+    }
+
+    async fn ensure_loaded(database: &Database) -> anyhow::Result<()> {
+        if CACHE.read().unwrap().is_empty() {
+            Self::refresh(database).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_crop(database: &Database, name: &str) -> anyhow::Result<Option<CropEntry>> {
+        Self::ensure_loaded(database).await?;
+        Ok(CACHE.read().unwrap().get(&name.to_lowercase()).cloned())
+    }
+
+    pub async fn validate_crop_type(database: &Database, crop: &str) -> anyhow::Result<bool> {
+        Self::ensure_loaded(database).await?;
+        Ok(CACHE.read().unwrap().contains_key(&crop.to_lowercase()))
+    }
+
+    pub async fn get_crop_synonyms(database: &Database, crop: &str) -> anyhow::Result<Vec<String>> {
+        Self::ensure_loaded(database).await?;
+        Ok(CACHE
+            .read()
+            .unwrap()
+            .get(&crop.to_lowercase())
+            .map(|entry| entry.synonyms.clone())
+            .unwrap_or_else(|| vec![crop.to_string()]))
+    }
+
+    /// Derives a symptom -> candidate-disease map from the crop's
+    /// `diseases` list, replacing the old hand-maintained match arms in
+    /// `SymptomService::get_expert_rules`.
+    pub async fn get_expert_rules(
+        database: &Database,
+        crop: &str,
+    ) -> anyhow::Result<HashMap<String, Vec<String>>> {
+        Self::ensure_loaded(database).await?;
+        let entry = CACHE.read().unwrap().get(&crop.to_lowercase()).cloned();
+
+        let mut rules: HashMap<String, Vec<String>> = HashMap::new();
+        if let Some(entry) = entry {
+            for disease in entry.diseases {
+                for symptom in disease.symptoms {
+                    rules
+                        .entry(symptom.to_lowercase().replace(' ', "_"))
+                        .or_default()
+                        .push(disease.name.clone());
+                }
+            }
+        }
+
+        Ok(rules)
+    }
+}
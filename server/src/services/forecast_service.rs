@@ -0,0 +1,336 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+
+use crate::database::Database;
+use crate::models::{GeoLocation, Severity};
+use crate::utils::calculate_distance;
+
+/// A forecast hour's per-disease pressure, both as the raw 0.0-1.0 score
+/// (for clients that want the continuous signal) and graded into the
+/// shared `Severity` enum (for clients that just want "how worried should
+/// I be").
+pub type RiskMap = HashMap<String, f64>;
+pub type SeverityMap = HashMap<String, Severity>;
+
+/// How long a cached forecast stays valid. Weather upstreams update on the
+/// order of hours, so this only needs to be long enough to absorb bursty
+/// polling from the same field, not to match their refresh cadence.
+const FORECAST_CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Rounds a coordinate to roughly 1km precision so nearby requests (e.g. a
+/// grower polling the same field repeatedly) hit the same cache entry
+/// instead of missing on floating-point noise.
+fn round_coord(value: f64) -> i64 {
+    (value * 100.0).round() as i64
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ForecastCacheKey {
+    lat: i64,
+    lon: i64,
+    crop: String,
+}
+
+type CachedForecast = BTreeMap<DateTime<Utc>, (RiskMap, SeverityMap)>;
+
+static FORECAST_CACHE: Lazy<RwLock<HashMap<ForecastCacheKey, (Instant, CachedForecast)>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy)]
+pub struct TimeRange {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// One provider's reading for a single point in time. `metrics` is an open
+/// bag (temperature_c, humidity_pct, outbreak_density, ...) so providers
+/// can contribute whatever signals they have without a shared schema.
+#[derive(Debug, Clone, Default)]
+pub struct Sample {
+    pub time: DateTime<Utc>,
+    pub metrics: std::collections::HashMap<String, f64>,
+}
+
+/// A source of time-series samples for a position, aggregated by
+/// `CombinedProvider` into a disease-risk forecast.
+#[async_trait]
+pub trait Provider: Send + Sync {
+    async fn fetch(&self, position: GeoLocation, time_range: TimeRange) -> Result<Vec<Sample>>;
+}
+
+/// Hourly temperature/humidity/leaf-wetness proxy (precipitation) from the
+/// configured weather API.
+pub struct WeatherSampleProvider {
+    base_url: String,
+}
+
+impl WeatherSampleProvider {
+    pub fn new(base_url: String) -> Self {
+        Self { base_url }
+    }
+}
+
+#[async_trait]
+impl Provider for WeatherSampleProvider {
+    async fn fetch(&self, position: GeoLocation, _time_range: TimeRange) -> Result<Vec<Sample>> {
+        let client = reqwest::Client::new();
+        let response: HourlyResponse = client
+            .get(format!("{}/forecast", self.base_url))
+            .query(&[
+                ("latitude", position.lat.to_string()),
+                ("longitude", position.lon.to_string()),
+                (
+                    "hourly",
+                    "temperature_2m,relative_humidity_2m,precipitation".to_string(),
+                ),
+                ("forecast_days", "3".to_string()),
+            ])
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let samples = response
+            .hourly
+            .time
+            .into_iter()
+            .zip(response.hourly.temperature_2m)
+            .zip(response.hourly.relative_humidity_2m)
+            .zip(response.hourly.precipitation)
+            .filter_map(|(((time, temp), humidity), precip)| {
+                let time = DateTime::parse_from_rfc3339(&time)
+                    .ok()
+                    .map(|t| t.with_timezone(&Utc))?;
+                let mut metrics = std::collections::HashMap::new();
+                metrics.insert("temperature_c".to_string(), temp);
+                metrics.insert("humidity_pct".to_string(), humidity);
+                metrics.insert("leaf_wetness_mm".to_string(), precip);
+                Some(Sample { time, metrics })
+            })
+            .collect();
+
+        Ok(samples)
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct HourlyResponse {
+    hourly: HourlySeries,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct HourlySeries {
+    time: Vec<String>,
+    temperature_2m: Vec<f64>,
+    relative_humidity_2m: Vec<f64>,
+    precipitation: Vec<f64>,
+}
+
+/// Recent nearby outbreak-report density, read straight from
+/// `/v1/outbreaks` territory via `Database::get_outbreaks_near`. Emitted
+/// as a single flat sample (outbreak pressure doesn't have a useful hourly
+/// shape) that every forecast hour can be scaled by.
+pub struct OutbreakDensityProvider<'a> {
+    database: &'a Database,
+    radius_km: f64,
+}
+
+impl<'a> OutbreakDensityProvider<'a> {
+    pub fn new(database: &'a Database, radius_km: f64) -> Self {
+        Self { database, radius_km }
+    }
+}
+
+#[async_trait]
+impl<'a> Provider for OutbreakDensityProvider<'a> {
+    async fn fetch(&self, position: GeoLocation, time_range: TimeRange) -> Result<Vec<Sample>> {
+        let reports = self
+            .database
+            .get_outbreaks_near(position.lat, position.lon, self.radius_km, 200)
+            .await?;
+
+        let count = reports
+            .iter()
+            .filter(|r| {
+                calculate_distance(position.lat, position.lon, r.latitude, r.longitude)
+                    <= self.radius_km
+            })
+            .count();
+
+        let mut metrics = std::collections::HashMap::new();
+        metrics.insert("outbreak_density".to_string(), count as f64);
+
+        Ok(vec![Sample {
+            time: time_range.start,
+            metrics,
+        }])
+    }
+}
+
+/// Fuses the weather and outbreak-density providers into a per-hour
+/// disease-risk forecast for a crop.
+pub struct CombinedProvider<'a> {
+    weather: WeatherSampleProvider,
+    outbreaks: OutbreakDensityProvider<'a>,
+}
+
+impl<'a> CombinedProvider<'a> {
+    pub fn new(weather_api_url: String, database: &'a Database, radius_km: f64) -> Self {
+        Self {
+            weather: WeatherSampleProvider::new(weather_api_url),
+            outbreaks: OutbreakDensityProvider::new(database, radius_km),
+        }
+    }
+
+    /// Builds the merged forecast keyed by timestamp, only filling in the
+    /// disease metrics the caller asked for (`requested_metrics`, empty
+    /// meaning "all relevant diseases for the crop"). Cached with a short
+    /// TTL, keyed by rounded position and crop, so repeated polling of the
+    /// same field doesn't re-hit the weather provider every request.
+    pub async fn forecast(
+        &self,
+        position: GeoLocation,
+        crop: &str,
+        requested_metrics: &[String],
+    ) -> Result<CachedForecast> {
+        let cache_key = ForecastCacheKey {
+            lat: round_coord(position.lat),
+            lon: round_coord(position.lon),
+            crop: crop.to_lowercase(),
+        };
+
+        if let Some((fetched_at, cached)) = FORECAST_CACHE.read().unwrap().get(&cache_key) {
+            if fetched_at.elapsed() < FORECAST_CACHE_TTL {
+                return Ok(filter_forecast(cached.clone(), requested_metrics));
+            }
+        }
+
+        let time_range = TimeRange {
+            start: Utc::now(),
+            end: Utc::now() + chrono::Duration::days(3),
+        };
+
+        let weather_samples = self.weather.fetch(position, time_range).await?;
+        let outbreak_samples = self.outbreaks.fetch(position, time_range).await?;
+        let outbreak_density = outbreak_samples
+            .first()
+            .and_then(|s| s.metrics.get("outbreak_density"))
+            .copied()
+            .unwrap_or(0.0);
+
+        let diseases = relevant_diseases(crop);
+
+        let mut forecast: CachedForecast = BTreeMap::new();
+        for sample in weather_samples {
+            let temp = sample.metrics.get("temperature_c").copied().unwrap_or(0.0);
+            let humidity = sample.metrics.get("humidity_pct").copied().unwrap_or(0.0);
+            let wetness = sample.metrics.get("leaf_wetness_mm").copied().unwrap_or(0.0);
+
+            let mut risk = RiskMap::new();
+            let mut severity = SeverityMap::new();
+            for disease in &diseases {
+                let score = disease_risk_score(disease, temp, humidity, wetness, outbreak_density);
+                risk.insert(disease.clone(), score);
+                severity.insert(disease.clone(), severity_from_risk(score));
+            }
+
+            forecast.insert(sample.time, (risk, severity));
+        }
+
+        FORECAST_CACHE
+            .write()
+            .unwrap()
+            .insert(cache_key, (Instant::now(), forecast.clone()));
+
+        Ok(filter_forecast(forecast, requested_metrics))
+    }
+}
+
+/// Narrows a cached (always computed for every relevant disease) forecast
+/// down to the diseases the caller asked for; empty means "all of them".
+fn filter_forecast(forecast: CachedForecast, requested_metrics: &[String]) -> CachedForecast {
+    if requested_metrics.is_empty() {
+        return forecast;
+    }
+
+    forecast
+        .into_iter()
+        .map(|(time, (risk, severity))| {
+            let risk = risk
+                .into_iter()
+                .filter(|(disease, _)| requested_metrics.contains(disease))
+                .collect();
+            let severity = severity
+                .into_iter()
+                .filter(|(disease, _)| requested_metrics.contains(disease))
+                .collect();
+            (time, (risk, severity))
+        })
+        .collect()
+}
+
+/// Grades a continuous 0.0-1.0 pressure score into the shared `Severity`
+/// enum; thresholds are tuned for forecast pressure, not diagnosis
+/// confidence, so this is deliberately separate from
+/// `handlers::outbreaks::determine_severity`.
+fn severity_from_risk(score: f64) -> Severity {
+    if score >= 0.7 {
+        Severity::Critical
+    } else if score >= 0.5 {
+        Severity::High
+    } else if score >= 0.3 {
+        Severity::Medium
+    } else {
+        Severity::Low
+    }
+}
+
+fn relevant_diseases(crop: &str) -> Vec<String> {
+    match crop.to_lowercase().as_str() {
+        "tomato" | "potato" => vec!["late_blight".to_string(), "early_blight".to_string()],
+        "cucumber" | "pepper" | "squash" => vec!["powdery_mildew".to_string()],
+        _ => vec!["generic_fungal_pressure".to_string()],
+    }
+}
+
+/// Warm, humid, sustained-wetness hours raise fungal risk; outbreak
+/// density nearby scales the whole score up since local inoculum pressure
+/// compounds favorable weather.
+fn disease_risk_score(
+    disease: &str,
+    temp_c: f64,
+    humidity_pct: f64,
+    leaf_wetness_mm: f64,
+    outbreak_density: f64,
+) -> f64 {
+    let temp_favorable = match disease {
+        "late_blight" => (10.0..=25.0).contains(&temp_c),
+        "early_blight" => (24.0..=29.0).contains(&temp_c),
+        "powdery_mildew" => (20.0..=28.0).contains(&temp_c),
+        _ => (15.0..=30.0).contains(&temp_c),
+    };
+
+    let mut score = 0.0;
+    if humidity_pct >= 85.0 {
+        score += 0.4;
+    } else if humidity_pct >= 70.0 {
+        score += 0.2;
+    }
+    if temp_favorable {
+        score += 0.3;
+    }
+    if leaf_wetness_mm > 0.0 {
+        score += 0.1;
+    }
+
+    let density_boost = (outbreak_density / 20.0).min(0.2);
+    (score + density_boost).min(1.0)
+}
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use axum::{extract::MatchedPath, extract::Request, middleware::Next, response::Response};
+use once_cell::sync::Lazy;
+
+/// Per-route request counters. Durations are tracked as cumulative
+/// microseconds rather than a real histogram (no bucketing) — enough to
+/// derive an average in Prometheus via
+/// `rate(leaflens_http_request_duration_seconds_sum) / rate(leaflens_http_requests_total)`,
+/// without pulling in a metrics crate for what's still a handful of
+/// routes.
+#[derive(Default)]
+struct RouteStats {
+    count: AtomicU64,
+    error_count: AtomicU64,
+    duration_micros_total: AtomicU64,
+}
+
+static ROUTE_STATS: Lazy<RwLock<HashMap<String, RouteStats>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Per-dependency call counters, fed by `DependencyTimer`. Keyed by a
+/// short static name (`"qdrant_health"`, `"db.get_outbreaks_in_region"`,
+/// ...) so a slow downstream (e.g. Qdrant health checks) shows up on its
+/// own line rather than being folded into the route it happened to run
+/// under.
+static DEPENDENCY_STATS: Lazy<RwLock<HashMap<&'static str, RouteStats>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Axum middleware layer: records a request count, error count (4xx/5xx),
+/// and cumulative latency per route. Mount with
+/// `middleware::from_fn(track_metrics)`.
+pub async fn track_metrics(req: Request, next: Next) -> Response {
+    // Key on the matched route template (e.g. `/v1/plugins/:id`), not the
+    // literal request path — otherwise every distinct id hit against a
+    // parameterized route grows `ROUTE_STATS` by one entry, forever, for
+    // any caller willing to vary the path.
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let start = Instant::now();
+
+    let response = next.run(req).await;
+
+    let elapsed = start.elapsed();
+    let is_error = response.status().is_client_error() || response.status().is_server_error();
+    record_request(&route, is_error, elapsed);
+
+    response
+}
+
+fn record_request(route: &str, is_error: bool, duration: Duration) {
+    let stats = ROUTE_STATS.read().unwrap();
+    if let Some(entry) = stats.get(route) {
+        update_stats(entry, is_error, duration);
+        return;
+    }
+    drop(stats);
+
+    let mut stats = ROUTE_STATS.write().unwrap();
+    let entry = stats.entry(route.to_string()).or_default();
+    update_stats(entry, is_error, duration);
+}
+
+fn update_stats(entry: &RouteStats, is_error: bool, duration: Duration) {
+    entry.count.fetch_add(1, Ordering::Relaxed);
+    if is_error {
+        entry.error_count.fetch_add(1, Ordering::Relaxed);
+    }
+    entry
+        .duration_micros_total
+        .fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+}
+
+/// Times a single call to an external dependency (database, Qdrant, the ML
+/// runtime, ...) and records its duration when dropped, so a slow
+/// downstream shows up as its own metric regardless of which route
+/// happened to trigger it.
+///
+/// ```ignore
+/// let _timer = DependencyTimer::start("qdrant_health");
+/// check_qdrant_health(&config.qdrant_url).await?;
+/// ```
+pub struct DependencyTimer {
+    name: &'static str,
+    start: Instant,
+}
+
+impl DependencyTimer {
+    pub fn start(name: &'static str) -> Self {
+        Self {
+            name,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for DependencyTimer {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        let stats = DEPENDENCY_STATS.read().unwrap();
+        if let Some(entry) = stats.get(self.name) {
+            update_stats(entry, false, elapsed);
+            return;
+        }
+        drop(stats);
+
+        let mut stats = DEPENDENCY_STATS.write().unwrap();
+        let entry = stats.entry(self.name).or_default();
+        update_stats(entry, false, elapsed);
+    }
+}
+
+/// Renders everything collected so far as Prometheus text exposition
+/// format, for `GET /metrics` (only mounted when `enable_telemetry` is
+/// true).
+pub fn render_prometheus() -> String {
+    let mut out = String::new();
+
+    out.push_str("# HELP leaflens_http_requests_total Total HTTP requests handled, by route.\n");
+    out.push_str("# TYPE leaflens_http_requests_total counter\n");
+    for (route, stats) in ROUTE_STATS.read().unwrap().iter() {
+        out.push_str(&format!(
+            "leaflens_http_requests_total{{route=\"{}\"}} {}\n",
+            route,
+            stats.count.load(Ordering::Relaxed)
+        ));
+    }
+
+    out.push_str("# HELP leaflens_http_request_errors_total Requests that returned a 4xx/5xx, by route.\n");
+    out.push_str("# TYPE leaflens_http_request_errors_total counter\n");
+    for (route, stats) in ROUTE_STATS.read().unwrap().iter() {
+        out.push_str(&format!(
+            "leaflens_http_request_errors_total{{route=\"{}\"}} {}\n",
+            route,
+            stats.error_count.load(Ordering::Relaxed)
+        ));
+    }
+
+    out.push_str("# HELP leaflens_http_request_duration_seconds_sum Cumulative request latency, by route.\n");
+    out.push_str("# TYPE leaflens_http_request_duration_seconds_sum counter\n");
+    for (route, stats) in ROUTE_STATS.read().unwrap().iter() {
+        let seconds = stats.duration_micros_total.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        out.push_str(&format!(
+            "leaflens_http_request_duration_seconds_sum{{route=\"{}\"}} {}\n",
+            route, seconds
+        ));
+    }
+
+    out.push_str("# HELP leaflens_dependency_call_duration_seconds_sum Cumulative time spent in external dependency calls.\n");
+    out.push_str("# TYPE leaflens_dependency_call_duration_seconds_sum counter\n");
+    for (name, stats) in DEPENDENCY_STATS.read().unwrap().iter() {
+        let seconds = stats.duration_micros_total.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        out.push_str(&format!(
+            "leaflens_dependency_call_duration_seconds_sum{{dependency=\"{}\"}} {}\n",
+            name, seconds
+        ));
+        out.push_str(&format!(
+            "leaflens_dependency_calls_total{{dependency=\"{}\"}} {}\n",
+            name,
+            stats.count.load(Ordering::Relaxed)
+        ));
+    }
+
+    out
+}
@@ -3,6 +3,12 @@ use image::{ImageBuffer, Rgb, RgbImage};
 use ndarray::{Array, Array3, Axis};
 use ort::{Environment, ExecutionProvider, Session, SessionBuilder, Value};
 use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::config::{Config, InferenceBackendKind};
+use crate::services::cluster_client::ClusterClient;
 
 #[derive(Debug, Clone)]
 pub struct MLPrediction {
@@ -10,35 +16,89 @@ pub struct MLPrediction {
     pub confidence: f64,
     pub category: String,
     pub metadata: Option<serde_json::Value>,
+    /// The classifier's penultimate (pre-logit) feature vector for this
+    /// image, shared across all predictions from the same inference call.
+    /// Used to match against past diagnoses via `case_memory`.
+    pub feature_vector: Option<Vec<f32>>,
+}
+
+/// Where classifier/segmentation inference actually runs: in-process via
+/// ONNX Runtime (today's path), or dispatched to a remote worker pool over
+/// the cluster message bus. See `services::cluster_client` for the
+/// `Remote` wire protocol; this mirrors the farmer/worker cluster split so
+/// the stateless Axum front-end can scale independently of GPU/CPU-bound
+/// model work.
+#[derive(Clone)]
+enum InferenceBackend {
+    Local(Arc<Session>),
+    Remote(ClusterClient),
 }
 
+/// Shared (behind `Arc`, via app state) across every request once
+/// `initialize` has loaded whatever models/backends `Config` asks for, so
+/// a cold ONNX session or cluster client isn't built per-request. `Clone`
+/// is shallow (`Arc<Session>`/already-`Clone` `ClusterClient`), matching
+/// how `Database`'s pool handle is shared.
+#[derive(Clone)]
 pub struct MLService {
-    classifier_session: Option<Session>,
-    segmentation_session: Option<Session>,
+    classifier: Option<InferenceBackend>,
+    segmentation: Option<InferenceBackend>,
+    /// Best-effort local models kept warm alongside a `Remote` backend so a
+    /// broker/worker outage degrades to local inference instead of failing
+    /// the request outright.
+    local_fallback_classifier: Option<Arc<Session>>,
+    local_fallback_segmentation: Option<Arc<Session>>,
+    /// Softmax temperature divisor applied to raw logits; see `Config::ml_temperature`.
+    temperature: f64,
 }
 
 impl MLService {
     pub fn new() -> Self {
         Self {
-            classifier_session: None,
-            segmentation_session: None,
+            classifier: None,
+            segmentation: None,
+            local_fallback_classifier: None,
+            local_fallback_segmentation: None,
+            temperature: 1.0,
         }
     }
 
-    pub async fn initialize(&mut self) -> Result<()> {
-        // Initialize ONNX runtime environment
-        let environment = Environment::builder()
-            .with_name("leaflens")
-            .build()?;
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = if temperature > 0.0 { temperature } else { 1.0 };
+        self
+    }
 
-        // Load classifier model
-        if let Ok(session) = Self::load_model(&environment, "models/leaflens_classifier.onnx").await {
-            self.classifier_session = Some(session);
-        }
+    pub async fn initialize(&mut self, config: &Config) -> Result<()> {
+        match config.inference_backend {
+            InferenceBackendKind::Local => {
+                let environment = Environment::builder().with_name("leaflens").build()?;
 
-        // Load segmentation model
-        if let Ok(session) = Self::load_model(&environment, "models/leaflens_segmentation.onnx").await {
-            self.segmentation_session = Some(session);
+                if let Ok(session) = Self::load_model(&environment, "models/leaflens_classifier.onnx").await {
+                    self.classifier = Some(InferenceBackend::Local(Arc::new(session)));
+                }
+                if let Ok(session) = Self::load_model(&environment, "models/leaflens_segmentation.onnx").await {
+                    self.segmentation = Some(InferenceBackend::Local(Arc::new(session)));
+                }
+            }
+            InferenceBackendKind::Remote => {
+                let client = ClusterClient::new(
+                    config.inference_broker_url.clone(),
+                    Duration::from_millis(config.inference_worker_timeout_ms),
+                );
+                self.classifier = Some(InferenceBackend::Remote(client.clone()));
+                self.segmentation = Some(InferenceBackend::Remote(client));
+
+                if let Ok(environment) = Environment::builder().with_name("leaflens-fallback").build() {
+                    self.local_fallback_classifier = Self::load_model(&environment, "models/leaflens_classifier.onnx")
+                        .await
+                        .ok()
+                        .map(Arc::new);
+                    self.local_fallback_segmentation = Self::load_model(&environment, "models/leaflens_segmentation.onnx")
+                        .await
+                        .ok()
+                        .map(Arc::new);
+                }
+            }
         }
 
         Ok(())
@@ -52,25 +112,102 @@ impl MLService {
     }
 
     pub async fn analyze_image(&self, image_data: &[u8]) -> Result<Vec<MLPrediction>> {
-        // Decode and preprocess image
         let processed_image = self.preprocess_image(image_data)?;
-        
-        // Run segmentation if available
-        let masked_image = if let Some(ref session) = self.segmentation_session {
-            self.segment_leaf(&processed_image, session)?
-        } else {
-            processed_image.clone()
-        };
 
-        // Run classification
-        let predictions = if let Some(ref session) = self.classifier_session {
-            self.classify_image(&masked_image, session)?
-        } else {
-            // Return mock predictions if no model is available
-            self.get_mock_predictions()
-        };
+        if self.classifier.is_none() {
+            return Ok(self.get_mock_predictions());
+        }
+
+        // One request id correlates every variant's worker round-trip when
+        // dispatching to a remote backend.
+        let request_id = Uuid::new_v4().to_string();
+
+        // Test-time augmentation: run the original image, a horizontal
+        // flip, and a center crop through the same pipeline and average
+        // their softmax probabilities. A single pass is sensitive to leaf
+        // orientation and lighting; averaging calibrated probabilities
+        // across variants is markedly more robust.
+        let variants = [
+            processed_image.clone(),
+            flip_horizontal(&processed_image),
+            center_crop_and_resize(&processed_image, 0.8),
+        ];
+
+        let mut probability_sum: Vec<f64> = Vec::new();
+        let mut feature_vector: Option<Vec<f32>> = None;
+
+        for variant in &variants {
+            let masked_image = self.segment(variant, &request_id).await?;
+            let (probabilities, variant_feature_vector) =
+                self.classify(&masked_image, &request_id).await?;
+
+            if probability_sum.is_empty() {
+                probability_sum = probabilities;
+            } else {
+                for (sum, p) in probability_sum.iter_mut().zip(probabilities) {
+                    *sum += p;
+                }
+            }
+            feature_vector = feature_vector.or(variant_feature_vector);
+        }
+
+        let variant_count = variants.len() as f64;
+        let averaged_probabilities: Vec<f64> = probability_sum
+            .into_iter()
+            .map(|p| p / variant_count)
+            .collect();
+
+        Ok(self.predictions_from_probabilities(&averaged_probabilities, feature_vector))
+    }
+
+    /// Runs segmentation through whichever backend is configured, with a
+    /// timeout + local-fallback policy for `Remote`: a broker/worker
+    /// failure falls back to a warm local model if one was loaded, and
+    /// otherwise leaves the image unmasked rather than failing the whole
+    /// diagnosis over a segmentation hiccup.
+    async fn segment(&self, image: &Array3<f32>, request_id: &str) -> Result<Array3<f32>> {
+        match self.segmentation.as_ref() {
+            None => Ok(image.clone()),
+            Some(InferenceBackend::Local(session)) => self.segment_local(image, session),
+            Some(InferenceBackend::Remote(client)) => match client.segment(request_id, image).await {
+                Ok(masked) => Ok(masked),
+                Err(e) => {
+                    if let Some(session) = self.local_fallback_segmentation.as_ref() {
+                        tracing::warn!("remote segmentation failed ({}), falling back to local model", e);
+                        self.segment_local(image, session)
+                    } else {
+                        tracing::warn!("remote segmentation failed ({}), using unsegmented image", e);
+                        Ok(image.clone())
+                    }
+                }
+            },
+        }
+    }
 
-        Ok(predictions)
+    /// Runs classification through whichever backend is configured, with
+    /// the same timeout + local-fallback policy as `segment`, except that a
+    /// `Remote` failure with no warm local model fails the call instead of
+    /// continuing.
+    async fn classify(
+        &self,
+        image: &Array3<f32>,
+        request_id: &str,
+    ) -> Result<(Vec<f64>, Option<Vec<f32>>)> {
+        match self.classifier.as_ref() {
+            None => unreachable!("analyze_image already returns mock predictions when no classifier is configured"),
+            Some(InferenceBackend::Local(session)) => self.classify_local(image, session),
+            Some(InferenceBackend::Remote(client)) => match client.classify(request_id, image).await {
+                Ok(result) => Ok(result),
+                Err(e) => {
+                    if let Some(session) = self.local_fallback_classifier.as_ref() {
+                        tracing::warn!("remote classification failed ({}), falling back to local model", e);
+                        self.classify_local(image, session)
+                    } else {
+                        Err(e)
+                    }
+                }
+            },
+        }
     }
 
     fn preprocess_image(&self, image_data: &[u8]) -> Result<Array3<f32>> {
@@ -99,7 +236,7 @@ impl MLService {
         Ok(array)
     }
 
-    fn segment_leaf(&self, image: &Array3<f32>, session: &Session) -> Result<Array3<f32>> {
+    fn segment_local(&self, image: &Array3<f32>, session: &Session) -> Result<Array3<f32>> {
         // Prepare input tensor
         let input_array = image.insert_axis(Axis(0)); // Add batch dimension
         let input_tensor = Value::from_array(input_array.view())?;
@@ -123,35 +260,59 @@ impl MLService {
         Ok(masked_image)
     }
 
-    fn classify_image(&self, image: &Array3<f32>, session: &Session) -> Result<Vec<MLPrediction>> {
+    /// Runs the classifier on a single preprocessed variant and returns a
+    /// true probability distribution (numerically-stable, temperature-
+    /// scaled softmax over the raw logits) alongside the feature vector.
+    /// Thresholding/ranking happens in `predictions_from_probabilities`,
+    /// once `analyze_image` has averaged the TTA variants.
+    fn classify_local(
+        &self,
+        image: &Array3<f32>,
+        session: &Session,
+    ) -> Result<(Vec<f64>, Option<Vec<f32>>)> {
         // Prepare input tensor
-        let input_array = image.insert_axis(Axis(0)); // Add batch dimension
+        let input_array = image.clone().insert_axis(Axis(0)); // Add batch dimension
         let input_tensor = Value::from_array(input_array.view())?;
 
-        // Run inference
+        // Run inference. Index 0 is the classifier's logits; index 1, when
+        // present, is the penultimate global-pool feature vector (e.g. a
+        // 512/1280-dim layer) we keep around for case-memory matching.
         let outputs = session.run(vec![input_tensor])?;
         let output = outputs[0].extract_tensor::<f32>()?;
-        let output_array = output.view();
+        let logits: Vec<f32> = output.view().iter().copied().collect();
 
-        // Process predictions
-        let mut predictions = Vec::new();
-        for i in 0..output_array.len() {
-            let confidence = output_array[i] as f64;
-            if confidence > 0.3 {
-                predictions.push(MLPrediction {
-                    label: self.get_label_for_index(i),
-                    confidence,
-                    category: self.get_category_for_index(i),
-                    metadata: None,
-                });
-            }
-        }
+        let feature_vector = outputs
+            .get(1)
+            .and_then(|t| t.extract_tensor::<f32>().ok())
+            .map(|t| t.view().iter().copied().collect::<Vec<f32>>());
+
+        Ok((softmax(&logits, self.temperature), feature_vector))
+    }
+
+    /// Thresholds, ranks, and truncates a probability distribution into the
+    /// API-facing prediction list.
+    fn predictions_from_probabilities(
+        &self,
+        probabilities: &[f64],
+        feature_vector: Option<Vec<f32>>,
+    ) -> Vec<MLPrediction> {
+        let mut predictions: Vec<MLPrediction> = probabilities
+            .iter()
+            .enumerate()
+            .filter(|(_, &confidence)| confidence > 0.3)
+            .map(|(i, &confidence)| MLPrediction {
+                label: self.get_label_for_index(i),
+                confidence,
+                category: self.get_category_for_index(i),
+                metadata: None,
+                feature_vector: feature_vector.clone(),
+            })
+            .collect();
 
-        // Sort by confidence and return top 5
         predictions.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap());
         predictions.truncate(5);
 
-        Ok(predictions)
+        predictions
     }
 
     fn get_mock_predictions(&self) -> Vec<MLPrediction> {
@@ -161,18 +322,21 @@ impl MLService {
                 confidence: 0.85,
                 category: "Healthy".to_string(),
                 metadata: None,
+                feature_vector: None,
             },
             MLPrediction {
                 label: "Bacterial Spot".to_string(),
                 confidence: 0.12,
                 category: "Disease".to_string(),
                 metadata: None,
+                feature_vector: None,
             },
             MLPrediction {
                 label: "Nutrient Deficiency".to_string(),
                 confidence: 0.08,
                 category: "Deficiency".to_string(),
                 metadata: None,
+                feature_vector: None,
             },
         ]
     }
@@ -213,4 +377,61 @@ impl Default for MLService {
     fn default() -> Self {
         Self::new()
     }
+}
+
+/// Numerically-stable, temperature-scaled softmax: subtracts the max
+/// scaled logit before exponentiating so large logits can't overflow, and
+/// divides by `temperature` first so the subtracted max is itself in
+/// temperature-scaled units (dividing by `T` after subtracting the raw max
+/// would not produce the same distribution).
+fn softmax(logits: &[f32], temperature: f64) -> Vec<f64> {
+    let temperature = if temperature > 0.0 { temperature } else { 1.0 };
+    let scaled: Vec<f64> = logits.iter().map(|&l| l as f64 / temperature).collect();
+    let max_scaled = scaled.iter().cloned().fold(f64::MIN, f64::max);
+
+    let exps: Vec<f64> = scaled.iter().map(|&s| (s - max_scaled).exp()).collect();
+    let sum: f64 = exps.iter().sum();
+
+    if sum <= 0.0 {
+        return vec![0.0; logits.len()];
+    }
+    exps.into_iter().map(|e| e / sum).collect()
+}
+
+/// Mirrors the image left-to-right; one of the test-time augmentation
+/// variants averaged in `MLService::analyze_image`.
+fn flip_horizontal(image: &Array3<f32>) -> Array3<f32> {
+    let (height, width, channels) = image.dim();
+    let mut flipped = Array3::<f32>::zeros((height, width, channels));
+    for y in 0..height {
+        for x in 0..width {
+            for c in 0..channels {
+                flipped[[y, x, c]] = image[[y, width - 1 - x, c]];
+            }
+        }
+    }
+    flipped
+}
+
+/// Crops the center `crop_fraction` of the image and nearest-neighbor
+/// resizes it back to the original dimensions, so it matches the model's
+/// fixed input shape like the other augmentation variants.
+fn center_crop_and_resize(image: &Array3<f32>, crop_fraction: f32) -> Array3<f32> {
+    let (height, width, channels) = image.dim();
+    let crop_height = ((height as f32) * crop_fraction).round().max(1.0) as usize;
+    let crop_width = ((width as f32) * crop_fraction).round().max(1.0) as usize;
+    let start_y = (height - crop_height) / 2;
+    let start_x = (width - crop_width) / 2;
+
+    let mut resized = Array3::<f32>::zeros((height, width, channels));
+    for y in 0..height {
+        let src_y = start_y + (y * crop_height) / height;
+        for x in 0..width {
+            let src_x = start_x + (x * crop_width) / width;
+            for c in 0..channels {
+                resized[[y, x, c]] = image[[src_y, src_x, c]];
+            }
+        }
+    }
+    resized
 }
\ No newline at end of file
@@ -0,0 +1,107 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::database::Database;
+use crate::models::PlaybookStep;
+
+/// A playbook document as published by the remote source, before it's
+/// written into the `playbooks` table. `content_version` is opaque to us —
+/// the source owns its own versioning scheme (a hash, a semver, a revision
+/// id) — we just use it to decide whether a re-run needs to write anything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestedPlaybook {
+    pub code: String,
+    pub title: String,
+    pub description: String,
+    pub steps: Vec<PlaybookStep>,
+    pub safety_notes: Vec<String>,
+    pub organic_alternatives: Option<Vec<String>>,
+    pub prevention_tips: Vec<String>,
+    pub content_version: String,
+}
+
+/// Source of playbook documents to ingest. Kept as a trait so the
+/// HTTP-backed implementation can be swapped for a mock without touching
+/// the ingestion/upsert logic, mirroring `WeatherProvider`.
+#[async_trait]
+pub trait PlaybookSource: Send + Sync {
+    async fn fetch_playbooks(&self) -> Result<Vec<IngestedPlaybook>>;
+}
+
+/// Pulls the full playbook set from the configured `playbook_source_url`.
+pub struct HttpPlaybookSource {
+    base_url: String,
+}
+
+impl HttpPlaybookSource {
+    pub fn new(base_url: String) -> Self {
+        Self { base_url }
+    }
+}
+
+#[async_trait]
+impl PlaybookSource for HttpPlaybookSource {
+    async fn fetch_playbooks(&self) -> Result<Vec<IngestedPlaybook>> {
+        let client = reqwest::Client::new();
+        let playbooks: Vec<IngestedPlaybook> = client
+            .get(format!("{}/playbooks", self.base_url))
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(playbooks)
+    }
+}
+
+/// Per-run counts so callers (and the admin endpoint) can tell at a glance
+/// whether a revision actually landed.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct IngestionSummary {
+    pub added: i64,
+    pub updated: i64,
+    pub unchanged: i64,
+}
+
+/// Fetches playbooks from `source` and upserts each one by `code`: a
+/// document replaces the stored row whenever its `content_version` differs.
+/// Versions are not ordered, so an older document from the source
+/// overwrites a newer stored one. Idempotent: re-running with unchanged
+/// documents touches nothing, since `Database::upsert_playbook` skips the
+/// write when `content_version` hasn't changed.
+pub async fn ingest_playbooks(
+    database: &Database,
+    source: &dyn PlaybookSource,
+) -> Result<IngestionSummary> {
+    let playbooks = source.fetch_playbooks().await?;
+    let mut summary = IngestionSummary::default();
+
+    for playbook in playbooks {
+        let steps = json!(playbook.steps);
+        let outcome = database
+            .upsert_playbook(
+                &playbook.code,
+                &playbook.title,
+                &playbook.description,
+                &steps,
+                &playbook.safety_notes,
+                playbook.organic_alternatives.as_deref(),
+                &playbook.prevention_tips,
+                &playbook.content_version,
+                chrono::Utc::now(),
+            )
+            .await?;
+
+        match outcome {
+            Some(true) => summary.added += 1,
+            Some(false) => summary.updated += 1,
+            None => summary.unchanged += 1,
+        }
+    }
+
+    Ok(summary)
+}
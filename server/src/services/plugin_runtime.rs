@@ -0,0 +1,128 @@
+use anyhow::{anyhow, Result};
+use wasmtime::{Config as WasmConfig, Engine, Linker, Module, Store};
+
+use crate::models::{PluginRunRequest, PluginRunResult};
+
+/// Fuel budget for a single `diagnose` call. Plugins are small, pure
+/// functions over a JSON blob, not long-running workloads, so this is
+/// generous headroom rather than a tuned limit.
+const PLUGIN_FUEL_LIMIT: u64 = 10_000_000;
+
+/// Wall-clock budget for a single `diagnose` call, enforced via wasmtime's
+/// epoch interruption. Fuel bounds the amount of WASM *work* but not real
+/// time, so this is a second, independent backstop on the guest code.
+const PLUGIN_TIME_LIMIT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Hard ceiling on the `PluginRunResult` a plugin can hand back. `out_len`
+/// comes straight from the plugin's own untrusted packed return value, so
+/// without this cap a malicious/buggy plugin returning an `out_len` near
+/// `u32::MAX` would force a multi-GB host allocation before `memory.read`
+/// is ever called.
+const PLUGIN_MAX_OUTPUT_BYTES: usize = 4 * 1024 * 1024;
+
+/// Executes third-party plugins as sandboxed WASM modules so a plugin can
+/// contribute diagnosis recommendations without running in-process with
+/// host privileges. Each call loads and instantiates the module fresh —
+/// plugins run rarely enough (once per `/v1/plugins/:id/run` request) that
+/// caching compiled `Module`s isn't worth the complexity yet.
+///
+/// Guest ABI: the guest exports linear memory as `memory`, `alloc(len: i32)
+/// -> i32` / `dealloc(ptr: i32, len: i32)` for the host to place input in
+/// and reclaim output from, and `diagnose(ptr: i32, len: i32) -> i64` which
+/// reads a UTF-8 JSON `PluginRunRequest` from `(ptr, len)` and returns a
+/// packed `(out_ptr << 32) | out_len` pointing at a UTF-8 JSON
+/// `PluginRunResult`.
+pub struct PluginRuntime {
+    engine: Engine,
+}
+
+impl PluginRuntime {
+    pub fn new() -> Result<Self> {
+        let mut config = WasmConfig::new();
+        config.consume_fuel(true);
+        config.epoch_interruption(true);
+        let engine = Engine::new(&config)?;
+        Ok(Self { engine })
+    }
+
+    pub async fn run(&self, wasm_bytes: &[u8], input: &PluginRunRequest) -> Result<PluginRunResult> {
+        let module = Module::new(&self.engine, wasm_bytes)
+            .map_err(|e| anyhow!("failed to load plugin module: {}", e))?;
+        let linker: Linker<()> = Linker::new(&self.engine);
+
+        let mut store = Store::new(&self.engine, ());
+        store.set_fuel(PLUGIN_FUEL_LIMIT)?;
+        store.set_epoch_deadline(1);
+
+        // Fuel bounds WASM instructions, not real time; bump the engine's
+        // epoch from a side thread after `PLUGIN_TIME_LIMIT` so guest code
+        // that's still executing then traps at its next epoch check. A
+        // no-op if `run` finishes first.
+        let deadline_engine = self.engine.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(PLUGIN_TIME_LIMIT);
+            deadline_engine.increment_epoch();
+        });
+
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .map_err(|e| anyhow!("failed to instantiate plugin module: {}", e))?;
+
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| anyhow!("plugin module does not export linear memory"))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&mut store, "alloc")
+            .map_err(|_| anyhow!("plugin module does not export alloc(len: i32) -> i32"))?;
+        let dealloc = instance
+            .get_typed_func::<(i32, i32), ()>(&mut store, "dealloc")
+            .map_err(|_| anyhow!("plugin module does not export dealloc(ptr: i32, len: i32)"))?;
+        let diagnose = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, "diagnose")
+            .map_err(|_| anyhow!("plugin module does not export diagnose(ptr: i32, len: i32) -> i64"))?;
+
+        let input_json = serde_json::to_vec(input)?;
+        let input_len = input_json.len() as i32;
+
+        let input_ptr = alloc
+            .call(&mut store, input_len)
+            .map_err(|e| anyhow!("plugin trapped during alloc: {}", e))?;
+        memory.write(&mut store, input_ptr as usize, &input_json)?;
+
+        let packed = diagnose
+            .call(&mut store, (input_ptr, input_len))
+            .map_err(|e| anyhow!("plugin ran out of fuel/time or trapped during diagnose: {}", e))?;
+
+        dealloc
+            .call(&mut store, (input_ptr, input_len))
+            .map_err(|e| anyhow!("plugin trapped during dealloc: {}", e))?;
+
+        let out_ptr = (packed >> 32) as u32 as usize;
+        let out_len = (packed & 0xFFFF_FFFF) as u32 as usize;
+
+        if out_len > PLUGIN_MAX_OUTPUT_BYTES {
+            return Err(anyhow!(
+                "plugin returned {} bytes, exceeding the {}-byte max",
+                out_len,
+                PLUGIN_MAX_OUTPUT_BYTES
+            ));
+        }
+        if out_ptr.checked_add(out_len).map_or(true, |end| end > memory.data_size(&store)) {
+            return Err(anyhow!("plugin returned an out-of-bounds output pointer/length"));
+        }
+
+        let mut result_bytes = vec![0u8; out_len];
+        memory.read(&store, out_ptr, &mut result_bytes)?;
+        dealloc.call(&mut store, (out_ptr as i32, out_len as i32)).ok();
+
+        let result: PluginRunResult = serde_json::from_slice(&result_bytes)
+            .map_err(|e| anyhow!("plugin returned malformed JSON: {}", e))?;
+        Ok(result)
+    }
+}
+
+impl Default for PluginRuntime {
+    fn default() -> Self {
+        Self::new().expect("failed to initialize wasmtime engine")
+    }
+}
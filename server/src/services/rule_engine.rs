@@ -0,0 +1,243 @@
+use once_cell::sync::Lazy;
+
+use crate::models::{Prediction, Priority, Recommendation};
+
+/// Read-only view of a diagnosis handed to every rule. Mirrors what a
+/// handler already has in scope by the time recommendations are generated,
+/// so a rule never needs more context than the handler itself does.
+pub struct DiagnosisContext<'a> {
+    pub predictions: &'a [Prediction],
+    pub crop: Option<&'a str>,
+    pub metadata: Option<&'a serde_json::Value>,
+    pub image: Option<&'a [u8]>,
+}
+
+/// A single diagnostic heuristic, analogous to a linter rule: stateless,
+/// independently registrable, and free to ignore a context it doesn't
+/// apply to by returning `None`.
+pub trait DiagnosticRule: Send + Sync {
+    fn name(&self) -> &str;
+    fn check(&self, ctx: &DiagnosisContext) -> Option<Vec<Recommendation>>;
+}
+
+/// Holds every registered rule and runs them in sequence. New heuristics
+/// (including ones contributed by plugins) register here instead of the
+/// handler growing another `match` arm.
+pub struct RuleRegistry {
+    rules: Vec<Box<dyn DiagnosticRule>>,
+}
+
+impl RuleRegistry {
+    pub fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    pub fn register(mut self, rule: Box<dyn DiagnosticRule>) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn rule_names(&self) -> Vec<String> {
+        self.rules.iter().map(|r| r.name().to_string()).collect()
+    }
+
+    /// Runs every rule and merges their output, keeping at most one
+    /// recommendation per title and preferring whichever copy has the
+    /// higher severity so a later rule can escalate an earlier finding
+    /// instead of appending a near-duplicate.
+    pub fn run(&self, ctx: &DiagnosisContext) -> Vec<Recommendation> {
+        let mut merged = Vec::new();
+        for rule in &self.rules {
+            if let Some(recs) = rule.check(ctx) {
+                merged = merge_recommendations(merged, recs);
+            }
+        }
+        merged
+    }
+}
+
+impl Default for RuleRegistry {
+    fn default() -> Self {
+        Self::new()
+            .register(Box::new(LowConfidenceRule))
+            .register(Box::new(CoOccurringFungalRule))
+            .register(Box::new(HealthyEnvironmentalStressRule))
+    }
+}
+
+/// The registry this build ships with, built once at process startup.
+pub static REGISTRY: Lazy<RuleRegistry> = Lazy::new(RuleRegistry::default);
+
+pub(crate) fn severity_rank(priority: &Priority) -> u8 {
+    match priority {
+        Priority::Low => 0,
+        Priority::Medium => 1,
+        Priority::High => 2,
+        Priority::Critical => 3,
+    }
+}
+
+/// Merges `additions` into `base`, keeping one entry per title and letting
+/// the higher-severity copy win ties. Shared by `RuleRegistry::run` and by
+/// handlers merging rule output alongside their own recommendations.
+pub fn merge_recommendations(
+    mut base: Vec<Recommendation>,
+    additions: Vec<Recommendation>,
+) -> Vec<Recommendation> {
+    for rec in additions {
+        match base.iter_mut().find(|r| r.title == rec.title) {
+            Some(existing) if severity_rank(&rec.priority) > severity_rank(&existing.priority) => {
+                *existing = rec;
+            }
+            Some(_) => {}
+            None => base.push(rec),
+        }
+    }
+    base
+}
+
+/// Low confidence across every prediction usually means a bad photo, not a
+/// bad model — ask for a better one rather than acting on a low-quality
+/// guess.
+struct LowConfidenceRule;
+
+impl DiagnosticRule for LowConfidenceRule {
+    fn name(&self) -> &str {
+        "low_confidence_photo"
+    }
+
+    fn check(&self, ctx: &DiagnosisContext) -> Option<Vec<Recommendation>> {
+        if ctx.predictions.is_empty() {
+            return None;
+        }
+
+        let max_confidence = ctx
+            .predictions
+            .iter()
+            .map(|p| p.confidence)
+            .fold(0.0_f64, f64::max);
+
+        if max_confidence >= 0.4 {
+            return None;
+        }
+
+        Some(vec![Recommendation {
+            title: "Retake the photo".to_string(),
+            description: "Confidence is too low to trust any single diagnosis".to_string(),
+            priority: Priority::Medium,
+            steps: vec![
+                "Retake the photo in even, natural light".to_string(),
+                "Fill the frame with a single affected leaf".to_string(),
+                "Avoid shadows and motion blur".to_string(),
+            ],
+            safety_notes: None,
+            organic_options: None,
+        }])
+    }
+}
+
+/// Same fungal-keyword check `weather_service::adjust_confidence_for_environment`
+/// uses to decide whether a label responds to humidity/temperature — kept
+/// here too so this rule only escalates on labels that are actually fungal,
+/// not any co-occurring Disease predictions.
+fn is_fungal_label(label: &str) -> bool {
+    let label = label.to_lowercase();
+    label.contains("mildew") || label.contains("blight") || label.contains("mold") || label.contains("rot")
+}
+
+/// Two fungal diseases showing up together signals a severe infection on
+/// the plant, not two independent minor issues — escalate instead of
+/// leaving both at their individually-computed priority.
+struct CoOccurringFungalRule;
+
+impl DiagnosticRule for CoOccurringFungalRule {
+    fn name(&self) -> &str {
+        "co_occurring_fungal_escalation"
+    }
+
+    fn check(&self, ctx: &DiagnosisContext) -> Option<Vec<Recommendation>> {
+        let fungal_labels: Vec<&str> = ctx
+            .predictions
+            .iter()
+            .filter(|p| p.category == "Disease" && p.confidence >= 0.3 && is_fungal_label(&p.label))
+            .map(|p| p.label.as_str())
+            .collect();
+
+        if fungal_labels.len() < 2 {
+            return None;
+        }
+
+        Some(vec![Recommendation {
+            title: "Escalate: multiple co-occurring diseases".to_string(),
+            description: format!(
+                "{} are present together, indicating a severe infection",
+                fungal_labels.join(" and ")
+            ),
+            priority: Priority::Critical,
+            steps: vec![
+                "Isolate the plant immediately".to_string(),
+                "Remove and destroy all visibly infected tissue".to_string(),
+                "Treat for every co-occurring disease, not just the top prediction".to_string(),
+            ],
+            safety_notes: Some("Disinfect tools and hands after handling".to_string()),
+            organic_options: None,
+        }])
+    }
+}
+
+/// A healthy top prediction alongside signs of environmental stress in the
+/// metadata (from the weather enrichment step) means the plant isn't
+/// diseased but isn't thriving either — point at care, not treatment.
+struct HealthyEnvironmentalStressRule;
+
+impl DiagnosticRule for HealthyEnvironmentalStressRule {
+    fn name(&self) -> &str {
+        "healthy_environmental_stress"
+    }
+
+    fn check(&self, ctx: &DiagnosisContext) -> Option<Vec<Recommendation>> {
+        let top = ctx.predictions.iter().max_by(|a, b| {
+            a.confidence
+                .partial_cmp(&b.confidence)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })?;
+
+        if top.category != "Healthy" {
+            return None;
+        }
+
+        let stressed = ctx
+            .metadata
+            .and_then(|m| m.get("environment"))
+            .map(|env| {
+                let humidity = env
+                    .get("humidity_pct")
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(50.0);
+                let temp = env
+                    .get("temperature_c")
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(20.0);
+                !(30.0..=85.0).contains(&humidity) || !(10.0..=32.0).contains(&temp)
+            })
+            .unwrap_or(false);
+
+        if !stressed {
+            return None;
+        }
+
+        Some(vec![Recommendation {
+            title: "Adjust watering for current conditions".to_string(),
+            description: "No disease detected, but recent conditions suggest environmental stress"
+                .to_string(),
+            priority: Priority::Low,
+            steps: vec![
+                "Check soil moisture before watering".to_string(),
+                "Water in the early morning to reduce evaporation loss".to_string(),
+                "Provide shade or a windbreak during extreme conditions".to_string(),
+            ],
+            safety_notes: None,
+            organic_options: None,
+        }])
+    }
+}
@@ -0,0 +1,200 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::{engine::general_purpose, Engine as _};
+use tokio::sync::Semaphore;
+
+use crate::database::Database;
+use crate::models::{BatchDiagnosisResult, DumpManifest, Prediction, TaskKind, TaskStatus};
+use crate::services::case_memory::CaseMemoryIndex;
+use crate::services::ml_service::MLService;
+
+/// Dequeues and runs `TaskKind` jobs (embedding, batch diagnosis, export
+/// dumps) off the persistent `tasks` table, so these stay off the request
+/// path; cluster recomputation and image reprocessing are not task kinds. Workers claim tasks with `SELECT ... FOR
+/// UPDATE SKIP LOCKED` (see `Database::next_task`), bounding concurrency
+/// with a semaphore rather than spawning unbounded tasks.
+pub struct Scheduler {
+    database: Database,
+    max_concurrency: usize,
+    poll_interval: Duration,
+    dump_dir: String,
+    ml_service: Arc<MLService>,
+}
+
+impl Scheduler {
+    pub fn new(database: Database, max_concurrency: usize, dump_dir: String, ml_service: Arc<MLService>) -> Self {
+        Self {
+            database,
+            max_concurrency,
+            poll_interval: Duration::from_secs(2),
+            dump_dir,
+            ml_service,
+        }
+    }
+
+    /// Runs the dequeue loop forever. Intended to be spawned as a
+    /// background tokio task from `main`.
+    pub async fn run(self) {
+        let semaphore = Arc::new(Semaphore::new(self.max_concurrency));
+
+        loop {
+            match self.database.next_task().await {
+                Ok(Some(task)) => {
+                    let permit = semaphore.clone().acquire_owned().await.expect("semaphore closed");
+                    let database = self.database.clone();
+                    let dump_dir = self.dump_dir.clone();
+                    let ml_service = self.ml_service.clone();
+                    tokio::spawn(async move {
+                        let _permit = permit;
+                        run_task(&database, task, &dump_dir, &ml_service).await;
+                    });
+                }
+                Ok(None) => tokio::time::sleep(self.poll_interval).await,
+                Err(e) => {
+                    tracing::error!("failed to dequeue task: {}", e);
+                    tokio::time::sleep(self.poll_interval).await;
+                }
+            }
+        }
+    }
+}
+
+async fn run_task(database: &Database, task: crate::database::Task, dump_dir: &str, ml_service: &MLService) {
+    // `Database::next_task` already persisted the `processing` transition
+    // before this runs, so a crash mid-task leaves the row visible as
+    // `processing` (not silently stuck `enqueued`); such a task is not
+    // resumed or re-queued.
+    let result = execute(database, &task, dump_dir, ml_service).await;
+
+    let status_update = match result {
+        Ok(result_value) => {
+            database
+                .set_task_result(task.id, TaskStatus::Succeeded, result_value.as_ref(), None)
+                .await
+        }
+        Err(e) => {
+            database
+                .set_task_result(task.id, TaskStatus::Failed, None, Some(&e.to_string()))
+                .await
+        }
+    };
+
+    if let Err(e) = status_update {
+        tracing::error!("failed to record status for task {}: {}", task.id, e);
+    }
+}
+
+async fn execute(
+    database: &Database,
+    task: &crate::database::Task,
+    dump_dir: &str,
+    ml_service: &MLService,
+) -> anyhow::Result<Option<serde_json::Value>> {
+    let kind: TaskKind = serde_json::from_value(task.content.clone())?;
+
+    match kind {
+        TaskKind::EmbedDiagnosis { diagnosis_id } => {
+            tracing::info!("embedding diagnosis {} into case base", diagnosis_id);
+            // The `diagnose` handler already inserts the vector into the
+            // live index synchronously for immediate matchability; this
+            // rebuild (one per saved diagnosis) folds it into the forest's
+            // splits and keeps them balanced as the case base grows.
+            CaseMemoryIndex::rebuild(database).await?;
+            Ok(None)
+        }
+        TaskKind::BatchDiagnose { images_base64, crop } => {
+            tracing::info!("running batch diagnosis over {} images", images_base64.len());
+            let results = run_batch_diagnosis(&images_base64, crop.as_deref(), ml_service).await;
+            Ok(Some(serde_json::to_value(results)?))
+        }
+        TaskKind::ExportDump {} => {
+            tracing::info!("running export dump {}", task.id);
+            let manifest = run_export_dump(database, dump_dir, task.id).await?;
+            Ok(Some(serde_json::to_value(manifest)?))
+        }
+    }
+}
+
+/// Serializes the current outbreaks, playbooks, and plugin registry into a
+/// single versioned archive under `dump_dir`, named after the task id so
+/// concurrent dumps never collide. Large exports run here rather than on
+/// the request path; the caller polls `GET /v1/diagnose/tasks/:id` for completion and
+/// then downloads via the manifest's `path`.
+async fn run_export_dump(
+    database: &Database,
+    dump_dir: &str,
+    task_id: uuid::Uuid,
+) -> anyhow::Result<DumpManifest> {
+    let outbreaks = database.get_recent_outbreaks(5000).await?;
+    let playbooks = database.get_all_playbooks().await?;
+    let plugins = database.get_all_plugins().await?;
+
+    let archive = serde_json::json!({
+        "dump_id": task_id,
+        "created_at": chrono::Utc::now(),
+        "outbreaks": outbreaks,
+        "playbooks": playbooks,
+        "plugins": plugins,
+    });
+
+    tokio::fs::create_dir_all(dump_dir).await?;
+    let path = format!("{}/dump-{}.json", dump_dir, task_id);
+    tokio::fs::write(&path, serde_json::to_vec_pretty(&archive)?).await?;
+
+    Ok(DumpManifest {
+        path,
+        outbreak_count: outbreaks.len() as i64,
+        playbook_count: playbooks.len() as i64,
+        plugin_count: plugins.len() as i64,
+        created_at: chrono::Utc::now(),
+    })
+}
+
+/// Runs `MLService::analyze_image` over every image in a batch, keeping
+/// going past a single image's decode/inference failure so one bad upload
+/// doesn't void the rest of the batch.
+async fn run_batch_diagnosis(
+    images_base64: &[String],
+    _crop: Option<&str>,
+    ml_service: &MLService,
+) -> Vec<BatchDiagnosisResult> {
+    let mut results = Vec::with_capacity(images_base64.len());
+
+    for (image_index, image_base64) in images_base64.iter().enumerate() {
+        let outcome = async {
+            let image_data = general_purpose::STANDARD.decode(image_base64)?;
+            let predictions = ml_service.analyze_image(&image_data).await?;
+            Ok::<Vec<crate::services::ml_service::MLPrediction>, anyhow::Error>(predictions)
+        }
+        .await;
+
+        results.push(match outcome {
+            Ok(predictions) => {
+                let confidence = predictions.iter().map(|p| p.confidence).fold(0.0, f64::max);
+                BatchDiagnosisResult {
+                    image_index,
+                    predictions: predictions
+                        .into_iter()
+                        .map(|p| Prediction {
+                            label: p.label,
+                            confidence: p.confidence,
+                            category: p.category,
+                            metadata: p.metadata,
+                        })
+                        .collect(),
+                    confidence,
+                    error: None,
+                }
+            }
+            Err(e) => BatchDiagnosisResult {
+                image_index,
+                predictions: Vec::new(),
+                confidence: 0.0,
+                error: Some(e.to_string()),
+            },
+        });
+    }
+
+    results
+}
@@ -0,0 +1,216 @@
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use tantivy::collector::TopDocs;
+use tantivy::query::{BooleanQuery, FuzzyTermQuery, Occur, Query, TermQuery};
+use tantivy::schema::{Field, IndexRecordOption, Schema, STORED, STRING, TEXT};
+use tantivy::{doc, Index, IndexReader, ReloadPolicy, Term};
+
+use crate::database::Database;
+
+/// Query terms at or above this length tolerate a single-character typo
+/// (insertion, deletion, or substitution); shorter terms must match
+/// exactly, since a Levenshtein-1 fuzzy match on e.g. "rot" would also
+/// match "rat", "lot", "rote", ...
+const FUZZY_MIN_TERM_LEN: usize = 5;
+const MAX_RESULTS: usize = 20;
+const SNIPPET_LEN: usize = 160;
+
+/// One hit returned by `search`: a playbook or outbreak report that
+/// matched the query, with the BM25 score tantivy assigned it.
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub kind: String,
+    pub ref_id: String,
+    pub title: String,
+    pub snippet: String,
+    pub score: f32,
+}
+
+struct Fields {
+    kind: Field,
+    ref_id: Field,
+    title: Field,
+    body: Field,
+    snippet: Field,
+}
+
+struct SearchIndex {
+    index: Index,
+    reader: IndexReader,
+    fields: Fields,
+}
+
+static HANDLE: Lazy<RwLock<Option<SearchIndex>>> = Lazy::new(|| RwLock::new(None));
+
+fn build_schema() -> (Schema, Fields) {
+    let mut builder = Schema::builder();
+    let kind = builder.add_text_field("kind", STRING | STORED);
+    let ref_id = builder.add_text_field("ref_id", STRING | STORED);
+    let title = builder.add_text_field("title", TEXT | STORED);
+    let body = builder.add_text_field("body", TEXT);
+    let snippet = builder.add_text_field("snippet", STORED);
+    let schema = builder.build();
+    (
+        schema,
+        Fields {
+            kind,
+            ref_id,
+            title,
+            body,
+            snippet,
+        },
+    )
+}
+
+fn truncate_snippet(text: &str) -> String {
+    if text.chars().count() <= SNIPPET_LEN {
+        text.to_string()
+    } else {
+        format!("{}...", text.chars().take(SNIPPET_LEN).collect::<String>())
+    }
+}
+
+/// Rebuilds the full-text index from the database: every ingested
+/// playbook plus the most recent outbreak reports. Call this at startup
+/// and again whenever playbook ingestion runs, so revised protocols are
+/// searchable without a restart.
+pub async fn rebuild(database: &Database) -> anyhow::Result<()> {
+    let (schema, fields) = build_schema();
+    let index = Index::create_in_ram(schema);
+    let mut writer = index.writer(15_000_000)?;
+
+    for playbook in database.get_all_playbooks().await? {
+        let steps_text = playbook
+            .steps
+            .as_array()
+            .map(|steps| {
+                steps
+                    .iter()
+                    .filter_map(|step| {
+                        let title = step.get("title")?.as_str().unwrap_or("");
+                        let description = step.get("description")?.as_str().unwrap_or("");
+                        Some(format!("{} {}", title, description))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .unwrap_or_default();
+
+        let body = format!(
+            "{} {} {} {}",
+            playbook.title,
+            playbook.description,
+            steps_text,
+            playbook.prevention_tips.join(" ")
+        );
+
+        writer.add_document(doc!(
+            fields.kind => "playbook",
+            fields.ref_id => playbook.code.clone(),
+            fields.title => playbook.title.clone(),
+            fields.body => body,
+            fields.snippet => truncate_snippet(&playbook.description),
+        ))?;
+    }
+
+    for outbreak in database.get_recent_outbreaks(5000).await? {
+        let title = format!("{} - {}", outbreak.crop_type, outbreak.disease);
+        let body = format!("{} {}", outbreak.crop_type, outbreak.disease);
+
+        writer.add_document(doc!(
+            fields.kind => "outbreak",
+            fields.ref_id => outbreak.id.to_string(),
+            fields.title => title.clone(),
+            fields.body => body,
+            fields.snippet => truncate_snippet(&title),
+        ))?;
+    }
+
+    writer.commit()?;
+
+    let reader = index
+        .reader_builder()
+        .reload_policy(ReloadPolicy::OnCommitWithDelay)
+        .try_into()?;
+
+    *HANDLE.write().unwrap() = Some(SearchIndex {
+        index,
+        reader,
+        fields,
+    });
+
+    Ok(())
+}
+
+/// Runs `q` against the live index. Terms of length >= `FUZZY_MIN_TERM_LEN`
+/// are matched with a Levenshtein edit distance of 1 (typo-tolerant);
+/// shorter terms must match exactly. Per-term queries are combined with a
+/// boolean OR and ranked by tantivy's default BM25 scoring.
+pub fn search(q: &str) -> Vec<SearchHit> {
+    let handle = HANDLE.read().unwrap();
+    let Some(search_index) = handle.as_ref() else {
+        return Vec::new();
+    };
+
+    let searcher = search_index.reader.searcher();
+    let terms: Vec<&str> = q.split_whitespace().collect();
+    if terms.is_empty() {
+        return Vec::new();
+    }
+
+    let subqueries: Vec<(Occur, Box<dyn Query>)> = terms
+        .iter()
+        .map(|term| {
+            let lower = term.to_lowercase();
+            let body_term = Term::from_field_text(search_index.fields.body, &lower);
+            let query: Box<dyn Query> = if lower.chars().count() >= FUZZY_MIN_TERM_LEN {
+                Box::new(FuzzyTermQuery::new(body_term, 1, true))
+            } else {
+                Box::new(TermQuery::new(body_term, IndexRecordOption::Basic))
+            };
+            (Occur::Should, query)
+        })
+        .collect();
+
+    let query = BooleanQuery::new(subqueries);
+
+    let top_docs = match searcher.search(&query, &TopDocs::with_limit(MAX_RESULTS)) {
+        Ok(docs) => docs,
+        Err(e) => {
+            tracing::warn!("search query failed: {}", e);
+            return Vec::new();
+        }
+    };
+
+    top_docs
+        .into_iter()
+        .filter_map(|(score, doc_address)| {
+            let retrieved = searcher.doc(doc_address).ok()?;
+            let kind = retrieved
+                .get_first(search_index.fields.kind)?
+                .as_text()?
+                .to_string();
+            let ref_id = retrieved
+                .get_first(search_index.fields.ref_id)?
+                .as_text()?
+                .to_string();
+            let title = retrieved
+                .get_first(search_index.fields.title)?
+                .as_text()?
+                .to_string();
+            let snippet = retrieved
+                .get_first(search_index.fields.snippet)?
+                .as_text()?
+                .to_string();
+
+            Some(SearchHit {
+                kind,
+                ref_id,
+                title,
+                snippet,
+                score,
+            })
+        })
+        .collect()
+}
@@ -16,6 +16,23 @@ pub struct PossibleCause {
     pub symptoms: Vec<String>,
 }
 
+/// Fixed dimensionality for the hashing-trick embeddings used until a real
+/// embedding model is wired in behind `embed_text`.
+const EMBEDDING_DIM: usize = 128;
+
+/// Weight given to the semantic (vector) score vs. the keyword/rule score
+/// when fusing the two retrievers in `search_similar_symptoms`.
+const SEMANTIC_RATIO: f64 = 0.6;
+
+const QDRANT_COLLECTION: &str = "symptom_cases";
+
+/// Minimum top-cause confidence before a symptom search is auto-indexed
+/// into the case base. `/v1/symptoms` is an unconfirmed guess endpoint, so
+/// indexing every query (including low-confidence ones) would let noisy
+/// guesses reinforce themselves; only reasonably confident matches are
+/// worth growing the case base with.
+const AUTO_INDEX_CONFIDENCE_THRESHOLD: f64 = 0.75;
+
 pub struct SymptomService;
 
 impl SymptomService {
@@ -23,11 +40,36 @@ impl SymptomService {
         crop_type: &str,
         symptoms: &[String],
         additional_info: Option<&str>,
+        qdrant_url: &str,
     ) -> Result<Vec<PossibleCause>> {
-        // For now, return mock analysis based on symptoms
-        // In a real implementation, this would use vector search with Qdrant
-        let possible_causes = Self::get_mock_analysis(crop_type, symptoms);
-        Ok(possible_causes)
+        let keyword_causes = Self::get_mock_analysis(crop_type, symptoms);
+
+        let semantic_causes =
+            match Self::search_similar_symptoms(crop_type, symptoms, qdrant_url).await {
+                Ok(causes) => causes,
+                Err(e) => {
+                    tracing::warn!("semantic symptom search failed, falling back to keyword-only: {}", e);
+                    Vec::new()
+                }
+            };
+
+        let mut causes = Self::hybrid_rank(keyword_causes, semantic_causes);
+
+        // Best-effort: grow the case base with what we just saw so future
+        // queries with similar wording get a semantic hit too. Only do this
+        // for confident matches; an unconfirmed low-confidence guess would
+        // otherwise let noise self-reinforce the case base.
+        if let Some(top) = causes.first().cloned() {
+            if top.confidence >= AUTO_INDEX_CONFIDENCE_THRESHOLD {
+                let text = Self::case_text(crop_type, symptoms, additional_info);
+                if let Err(e) = Self::index_case(qdrant_url, &text, &top).await {
+                    tracing::warn!("failed to auto-index symptom case: {}", e);
+                }
+            }
+        }
+
+        causes.truncate(5);
+        Ok(causes)
     }
 
     fn get_mock_analysis(crop_type: &str, symptoms: &[String]) -> Vec<PossibleCause> {
@@ -101,56 +143,190 @@ impl SymptomService {
         causes
     }
 
-    // This would be implemented with Qdrant vector search in a real system
+    /// Semantic retriever: embeds the query and searches the `symptom_cases`
+    /// Qdrant collection for the nearest stored cases.
     async fn search_similar_symptoms(
         crop_type: &str,
         symptoms: &[String],
+        qdrant_url: &str,
     ) -> Result<Vec<PossibleCause>> {
-        // TODO: Implement vector search with Qdrant
-        // 1. Convert symptoms to embeddings
-        // 2. Search similar cases in vector database
-        // 3. Return ranked possible causes
-        Ok(vec![])
+        let query_text = Self::case_text(crop_type, symptoms, None);
+        let vector = Self::embed_text(&query_text);
+
+        let hits = Self::qdrant_search(qdrant_url, &vector, 10).await?;
+
+        let causes = hits
+            .into_iter()
+            .filter_map(|hit| {
+                let payload = hit.payload?;
+                Some(PossibleCause {
+                    name: payload.name,
+                    confidence: hit.score as f64,
+                    category: payload.category,
+                    description: payload.description,
+                    symptoms: payload.symptoms,
+                })
+            })
+            .collect();
+
+        Ok(causes)
     }
 
-    // This would use a knowledge base or expert system
-    fn get_expert_rules(crop_type: &str) -> HashMap<String, Vec<String>> {
-        let mut rules = HashMap::new();
-        
-        match crop_type.to_lowercase().as_str() {
-            "tomato" => {
-                rules.insert("yellowing_leaves".to_string(), vec![
-                    "Nitrogen Deficiency".to_string(),
-                    "Overwatering".to_string(),
-                    "Fusarium Wilt".to_string(),
-                ]);
-                rules.insert("brown_spots".to_string(), vec![
-                    "Bacterial Spot".to_string(),
-                    "Early Blight".to_string(),
-                    "Late Blight".to_string(),
-                ]);
-            }
-            "pepper" => {
-                rules.insert("yellowing_leaves".to_string(), vec![
-                    "Nutrient Deficiency".to_string(),
-                    "Aphid Damage".to_string(),
-                    "Viral Infection".to_string(),
-                ]);
-                rules.insert("brown_spots".to_string(), vec![
-                    "Bacterial Spot".to_string(),
-                    "Anthracnose".to_string(),
-                    "Sunscald".to_string(),
-                ]);
-            }
-            _ => {
-                rules.insert("general_symptoms".to_string(), vec![
-                    "Environmental Stress".to_string(),
-                    "Nutrient Imbalance".to_string(),
-                    "Pest Damage".to_string(),
-                ]);
+    /// Fuses the keyword retriever's results with the semantic retriever's
+    /// results: both score sets are normalized to [0, 1], combined with
+    /// `SEMANTIC_RATIO`, then deduped by `name` and sorted descending.
+    fn hybrid_rank(
+        keyword_causes: Vec<PossibleCause>,
+        semantic_causes: Vec<PossibleCause>,
+    ) -> Vec<PossibleCause> {
+        let normalize = |causes: &[PossibleCause]| -> HashMap<String, f64> {
+            let max = causes
+                .iter()
+                .map(|c| c.confidence)
+                .fold(0.0_f64, f64::max)
+                .max(f64::EPSILON);
+            causes
+                .iter()
+                .map(|c| (c.name.clone(), c.confidence / max))
+                .collect()
+        };
+
+        let keyword_scores = normalize(&keyword_causes);
+        let semantic_scores = normalize(&semantic_causes);
+
+        let mut merged: HashMap<String, PossibleCause> = HashMap::new();
+        for cause in keyword_causes.into_iter().chain(semantic_causes.into_iter()) {
+            merged.entry(cause.name.clone()).or_insert(cause);
+        }
+
+        let mut ranked: Vec<PossibleCause> = merged
+            .into_values()
+            .map(|mut cause| {
+                let keyword_score = keyword_scores.get(&cause.name).copied().unwrap_or(0.0);
+                let semantic_score = semantic_scores.get(&cause.name).copied().unwrap_or(0.0);
+                cause.confidence =
+                    SEMANTIC_RATIO * semantic_score + (1.0 - SEMANTIC_RATIO) * keyword_score;
+                cause
+            })
+            .collect();
+
+        ranked.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap());
+        ranked
+    }
+
+    /// Autoembedding hook: upserts a symptom case whose top cause cleared
+    /// `AUTO_INDEX_CONFIDENCE_THRESHOLD` into the Qdrant case base so future
+    /// queries can find it semantically.
+    async fn index_case(qdrant_url: &str, text: &str, cause: &PossibleCause) -> Result<()> {
+        let vector = Self::embed_text(text);
+        let payload = QdrantPayload {
+            name: cause.name.clone(),
+            category: cause.category.clone(),
+            description: cause.description.clone(),
+            symptoms: cause.symptoms.clone(),
+        };
+        Self::qdrant_upsert(qdrant_url, &vector, &payload).await
+    }
+
+    fn case_text(crop_type: &str, symptoms: &[String], additional_info: Option<&str>) -> String {
+        let mut parts = vec![crop_type.to_string(), symptoms.join(", ")];
+        if let Some(info) = additional_info {
+            parts.push(info.to_string());
+        }
+        parts.join(" ")
+    }
+
+    /// Deterministic hashing-trick embedding. Stands in for a real sentence
+    /// embedding model; swap this out once one is available without
+    /// changing the Qdrant plumbing below.
+    fn embed_text(text: &str) -> Vec<f32> {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let mut vector = vec![0.0_f32; EMBEDDING_DIM];
+        for token in text.to_lowercase().split_whitespace() {
+            let mut hasher = DefaultHasher::new();
+            token.hash(&mut hasher);
+            let bucket = (hasher.finish() as usize) % EMBEDDING_DIM;
+            vector[bucket] += 1.0;
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for v in vector.iter_mut() {
+                *v /= norm;
             }
         }
-        
-        rules
+
+        vector
+    }
+
+    async fn qdrant_search(
+        qdrant_url: &str,
+        vector: &[f32],
+        limit: usize,
+    ) -> Result<Vec<QdrantHit>> {
+        let client = reqwest::Client::new();
+        let response = client
+            .post(format!(
+                "{}/collections/{}/points/search",
+                qdrant_url, QDRANT_COLLECTION
+            ))
+            .json(&serde_json::json!({
+                "vector": vector,
+                "limit": limit,
+                "with_payload": true,
+            }))
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let body: QdrantSearchResponse = response.json().await?;
+        Ok(body.result)
+    }
+
+    async fn qdrant_upsert(qdrant_url: &str, vector: &[f32], payload: &QdrantPayload) -> Result<()> {
+        let client = reqwest::Client::new();
+        client
+            .put(format!(
+                "{}/collections/{}/points",
+                qdrant_url, QDRANT_COLLECTION
+            ))
+            .json(&serde_json::json!({
+                "points": [{
+                    "id": uuid::Uuid::new_v4().to_string(),
+                    "vector": vector,
+                    "payload": payload,
+                }]
+            }))
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
     }
-}
\ No newline at end of file
+
+    // Expert rules now live in `CropKnowledgeBase::get_expert_rules`,
+    // derived from the database-backed crop/disease table.
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct QdrantPayload {
+    name: String,
+    category: String,
+    description: String,
+    symptoms: Vec<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct QdrantHit {
+    score: f32,
+    payload: Option<QdrantPayload>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct QdrantSearchResponse {
+    result: Vec<QdrantHit>,
+}
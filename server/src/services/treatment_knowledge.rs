@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+/// Specific, per-disease treatment guidance. Replaces the old
+/// category-only advice ("Disease" -> the same four generic steps) with
+/// something that actually differs between e.g. Late Blight and
+/// Bacterial Spot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Treatment {
+    pub crop: String,
+    pub label: String,
+    pub chemical_remedies: Vec<String>,
+    pub organic_remedies: Vec<String>,
+    pub application_cadence: String,
+    pub safety_notes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TreatmentFile {
+    #[serde(default, rename = "treatment")]
+    treatments: Vec<Treatment>,
+}
+
+/// Bundled at build time from `assets/treatments.toml`; loaded once into a
+/// `(crop, label)` map on first lookup.
+static TREATMENTS: Lazy<HashMap<(String, String), Treatment>> = Lazy::new(|| {
+    let raw = include_str!("../../assets/treatments.toml");
+    let parsed: TreatmentFile = toml::from_str(raw).unwrap_or(TreatmentFile {
+        treatments: Vec::new(),
+    });
+
+    parsed
+        .treatments
+        .into_iter()
+        .map(|t| ((t.crop.to_lowercase(), t.label.to_lowercase()), t))
+        .collect()
+});
+
+/// Looks up the exact `(crop_type, label)` match. Callers should fall back
+/// to their generic category-level advice when this returns `None`.
+pub fn lookup_treatment(crop_type: &str, label: &str) -> Option<&'static Treatment> {
+    TREATMENTS.get(&(crop_type.to_lowercase(), label.to_lowercase()))
+}
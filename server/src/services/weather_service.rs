@@ -0,0 +1,122 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::models::GeoLocation;
+
+/// A point-in-time environmental reading for a `GeoLocation`, stored
+/// alongside diagnoses/outbreak reports in their `metadata` column so it
+/// can be queried later without re-fetching from the provider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvironmentalSnapshot {
+    pub temperature_c: f64,
+    pub humidity_pct: f64,
+    pub recent_rainfall_mm: f64,
+    pub air_quality_index: f64,
+    pub fetched_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Source of environmental conditions for a location. Kept as a trait so
+/// the HTTP-backed implementation can be swapped for a mock in tests or a
+/// different provider without touching the confidence-adjustment logic.
+#[async_trait]
+pub trait WeatherProvider: Send + Sync {
+    async fn fetch(&self, location: &GeoLocation) -> Result<EnvironmentalSnapshot>;
+}
+
+/// Fetches current conditions and recent rainfall/air-quality from the
+/// configured `weather_api_url`.
+pub struct HttpWeatherProvider {
+    base_url: String,
+}
+
+impl HttpWeatherProvider {
+    pub fn new(base_url: String) -> Self {
+        Self { base_url }
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for HttpWeatherProvider {
+    async fn fetch(&self, location: &GeoLocation) -> Result<EnvironmentalSnapshot> {
+        let client = reqwest::Client::new();
+        let response: OpenMeteoResponse = client
+            .get(format!("{}/forecast", self.base_url))
+            .query(&[
+                ("latitude", location.lat.to_string()),
+                ("longitude", location.lon.to_string()),
+                (
+                    "current",
+                    "temperature_2m,relative_humidity_2m,precipitation".to_string(),
+                ),
+                ("hourly", "us_aqi".to_string()),
+            ])
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(EnvironmentalSnapshot {
+            temperature_c: response.current.temperature_2m,
+            humidity_pct: response.current.relative_humidity_2m,
+            recent_rainfall_mm: response.current.precipitation,
+            air_quality_index: response.hourly.and_then(|h| h.us_aqi.first().copied()).unwrap_or(0.0),
+            fetched_at: chrono::Utc::now(),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoResponse {
+    current: OpenMeteoCurrent,
+    hourly: Option<OpenMeteoHourly>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoCurrent {
+    temperature_2m: f64,
+    relative_humidity_2m: f64,
+    precipitation: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoHourly {
+    us_aqi: Vec<f64>,
+}
+
+/// Agronomic modifiers applied on top of a model/rule confidence score.
+/// Each disease category responds differently to humidity/temperature, so
+/// this only nudges confidence rather than overriding it outright.
+pub fn adjust_confidence_for_environment(
+    label: &str,
+    category: &str,
+    base_confidence: f64,
+    snapshot: &EnvironmentalSnapshot,
+) -> f64 {
+    let is_fungal = category.eq_ignore_ascii_case("Disease")
+        && (label.to_lowercase().contains("mildew")
+            || label.to_lowercase().contains("blight")
+            || label.to_lowercase().contains("mold")
+            || label.to_lowercase().contains("rot"));
+
+    let mut adjusted = base_confidence;
+
+    if is_fungal {
+        let humidity_favorable = snapshot.humidity_pct >= 80.0;
+        let temp_favorable = (10.0..=25.0).contains(&snapshot.temperature_c);
+        if humidity_favorable && temp_favorable {
+            adjusted = (adjusted + 0.15).min(1.0);
+        } else if humidity_favorable || temp_favorable {
+            adjusted = (adjusted + 0.05).min(1.0);
+        }
+    }
+
+    if category.eq_ignore_ascii_case("Pest") && snapshot.recent_rainfall_mm < 1.0 {
+        // Many leaf-chewing pests are more active in dry spells.
+        adjusted = (adjusted + 0.05).min(1.0);
+    }
+
+    adjusted
+}
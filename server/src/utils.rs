@@ -1,4 +1,5 @@
 use anyhow::Result;
+use image::{Rgba, RgbaImage};
 use serde_json::Value;
 
 pub fn validate_image_format(image_data: &[u8]) -> Result<()> {
@@ -97,11 +98,35 @@ pub fn validate_coordinates(lat: f64, lon: f64) -> Result<()> {
     if lat < -90.0 || lat > 90.0 {
         return Err(anyhow::anyhow!("Invalid latitude: {}", lat));
     }
-    
+
     if lon < -180.0 || lon > 180.0 {
         return Err(anyhow::anyhow!("Invalid longitude: {}", lon));
     }
-    
+
+    Ok(())
+}
+
+/// Hard ceilings on `get_outbreaks_near`'s `radius_km`/`limit` query
+/// parameters. Without these, an unbounded `radius_km` pulls the entire
+/// `outbreak_reports` table into application memory before `limit` is ever
+/// applied — the same class of bug `MAX_TILE_DIMENSION` closed for heatmap
+/// tiles.
+pub const MAX_NEAR_RADIUS_KM: f64 = 500.0;
+pub const MAX_NEAR_LIMIT: i64 = 500;
+
+pub fn validate_near_query(radius_km: f64, limit: i64) -> Result<()> {
+    if radius_km <= 0.0 || radius_km > MAX_NEAR_RADIUS_KM {
+        return Err(anyhow::anyhow!(
+            "radius_km {} out of range (0, {}]",
+            radius_km,
+            MAX_NEAR_RADIUS_KM
+        ));
+    }
+
+    if limit <= 0 || limit > MAX_NEAR_LIMIT {
+        return Err(anyhow::anyhow!("limit {} out of range (0, {}]", limit, MAX_NEAR_LIMIT));
+    }
+
     Ok(())
 }
 
@@ -122,6 +147,126 @@ pub fn calculate_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
     earth_radius * c
 }
 
+/// A single weighted sample fed into `render_heatmap_tile`, typically one
+/// outbreak report reduced to its location and confidence.
+#[derive(Debug, Clone, Copy)]
+pub struct HeatmapPoint {
+    pub lat: f64,
+    pub lon: f64,
+    pub weight: f64,
+}
+
+/// Hard ceiling on a heatmap tile's `width`/`height`. Callers should
+/// reject out-of-range dimensions before reaching this function (see
+/// `handlers::outbreaks::get_outbreak_heatmap`); this check is a backstop
+/// so a `width * height` grid allocation can never run away regardless of
+/// who calls in.
+pub const MAX_TILE_DIMENSION: u32 = 2048;
+
+/// Renders a `crate::models::Region` bounding box into a raster density
+/// heatmap PNG: buckets `points` into a `width`x`height` grid weighted by
+/// `weight` (report count x confidence upstream), box-blurs the grid to
+/// smooth out single-cell spikes, then maps each cell's intensity through
+/// a blue -> yellow -> red color ramp.
+pub fn render_heatmap_tile(
+    region: &crate::models::Region,
+    width: u32,
+    height: u32,
+    points: &[HeatmapPoint],
+) -> Result<Vec<u8>> {
+    if width > MAX_TILE_DIMENSION || height > MAX_TILE_DIMENSION {
+        return Err(anyhow::anyhow!(
+            "tile dimensions {}x{} exceed the {}x{} max",
+            width,
+            height,
+            MAX_TILE_DIMENSION,
+            MAX_TILE_DIMENSION
+        ));
+    }
+    let (width, height) = (width.max(1), height.max(1));
+    let mut grid = vec![0.0_f64; (width * height) as usize];
+
+    let lat_span = (region.max_lat - region.min_lat).max(f64::EPSILON);
+    let lon_span = (region.max_lon - region.min_lon).max(f64::EPSILON);
+
+    for point in points {
+        if point.lat < region.min_lat
+            || point.lat > region.max_lat
+            || point.lon < region.min_lon
+            || point.lon > region.max_lon
+        {
+            continue;
+        }
+
+        let x = (((point.lon - region.min_lon) / lon_span) * (width as f64 - 1.0)) as u32;
+        let y = ((1.0 - (point.lat - region.min_lat) / lat_span) * (height as f64 - 1.0)) as u32;
+        grid[(y * width + x) as usize] += point.weight;
+    }
+
+    let smoothed = box_blur(&grid, width, height);
+
+    let max_intensity = smoothed.iter().cloned().fold(0.0_f64, f64::max).max(f64::EPSILON);
+
+    let mut image = RgbaImage::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            let intensity = smoothed[(y * width + x) as usize] / max_intensity;
+            image.put_pixel(x, y, intensity_to_color(intensity));
+        }
+    }
+
+    let mut buffer = Vec::new();
+    let mut cursor = std::io::Cursor::new(&mut buffer);
+    image.write_to(&mut cursor, image::ImageFormat::Png)?;
+
+    Ok(buffer)
+}
+
+fn box_blur(grid: &[f64], width: u32, height: u32) -> Vec<f64> {
+    let (width, height) = (width as i64, height as i64);
+    let mut out = vec![0.0_f64; grid.len()];
+
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = 0.0;
+            let mut count = 0.0;
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let (nx, ny) = (x + dx, y + dy);
+                    if nx >= 0 && nx < width && ny >= 0 && ny < height {
+                        sum += grid[(ny * width + nx) as usize];
+                        count += 1.0;
+                    }
+                }
+            }
+            out[(y * width + x) as usize] = sum / count;
+        }
+    }
+
+    out
+}
+
+/// Blue (cold) -> yellow -> red (hot) color ramp, alpha scaled by
+/// intensity so empty cells render fully transparent.
+fn intensity_to_color(intensity: f64) -> Rgba<u8> {
+    let intensity = intensity.clamp(0.0, 1.0);
+
+    let (r, g, b) = if intensity < 0.5 {
+        let t = intensity * 2.0;
+        (t, t, 1.0 - t)
+    } else {
+        let t = (intensity - 0.5) * 2.0;
+        (1.0, 1.0 - t, 0.0)
+    };
+
+    Rgba([
+        (r * 255.0) as u8,
+        (g * 255.0) as u8,
+        (b * 255.0) as u8,
+        (intensity * 200.0) as u8,
+    ])
+}
+
 pub fn generate_diagnosis_id() -> String {
     use uuid::Uuid;
     Uuid::new_v4().to_string()
@@ -131,28 +276,6 @@ pub fn parse_crop_type(crop: &str) -> String {
     crop.to_lowercase().replace(" ", "_")
 }
 
-pub fn validate_crop_type(crop: &str) -> bool {
-    const VALID_CROPS: &[&str] = &[
-        "tomato", "pepper", "cucumber", "lettuce", "spinach", "carrot",
-        "onion", "garlic", "potato", "corn", "beans", "peas", "broccoli",
-        "cauliflower", "cabbage", "kale", "chard", "beet", "radish",
-        "turnip", "parsnip", "celery", "asparagus", "artichoke",
-    ];
-    
-    VALID_CROPS.contains(&crop.to_lowercase().as_str())
-}
-
-pub fn get_crop_synonyms(crop: &str) -> Vec<String> {
-    let synonyms: std::collections::HashMap<&str, Vec<&str>> = [
-        ("tomato", vec!["tomatoes", "tomato plant", "lycopersicon"]),
-        ("pepper", vec!["peppers", "bell pepper", "capsicum", "chili"]),
-        ("cucumber", vec!["cucumbers", "cucumis"]),
-        ("lettuce", vec!["lettuces", "lactuca"]),
-        ("potato", vec!["potatoes", "solanum tuberosum"]),
-    ].iter().cloned().collect();
-    
-    synonyms
-        .get(crop.to_lowercase().as_str())
-        .map(|syns| syns.iter().map(|s| s.to_string()).collect())
-        .unwrap_or_else(|| vec![crop.to_string()])
-}
\ No newline at end of file
+// `validate_crop_type` and `get_crop_synonyms` moved to
+// `services::crop_knowledge::CropKnowledgeBase`, which backs them with the
+// database-driven crop table instead of a compile-time list.
\ No newline at end of file